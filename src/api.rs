@@ -0,0 +1,503 @@
+//! Figma REST API requests
+
+use std::{
+    collections::{BTreeSet, HashSet},
+    path::Path,
+    time::Duration,
+};
+
+use reqwest::{header::RETRY_AFTER, StatusCode};
+
+use crate::cli::{AccessToken, FigmaConfig};
+use crate::prelude::*;
+use crate::types::{
+    export_format::ExportFormat,
+    file::{FigmaData, FileMeta, FileNodes, ImageFills, Images},
+    node::Node,
+    variables::{LocalVariables, Variables},
+};
+use crate::utils::{
+    assets::{image_extension, image_paths, Asset, IMAGES_DIR},
+    filter::Filter,
+};
+
+pub const DEFAULT_API_URL: &str = "https://api.figma.com/v1";
+
+// Keeps the `/nodes` urls within a safe length
+const NODES_PER_REQUEST: usize = 50;
+
+// Upper limit of the wait between retries
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// HTTP layer of the Figma requests, so they can be served from a local server or recorded fixtures
+pub trait HttpClient {
+    /// GET request to a path relative to the API base url, ex: `/files/:key`
+    async fn get(&self, path: &str) -> Result<Vec<u8>>;
+
+    /// GET request to an absolute url outside of the API, like the rendered images, without the access token
+    async fn download(&self, url: &str) -> Result<Vec<u8>>;
+}
+
+pub struct FigmaClient {
+    client: reqwest::Client,
+    api_url: String,
+    token: AccessToken,
+    retries: u32,
+    backoff: Duration,
+}
+
+impl FigmaClient {
+    pub fn new(figma_config: &FigmaConfig) -> Result<FigmaClient> {
+        let http = &figma_config.http;
+        let mut builder = reqwest::Client::builder()
+            .connect_timeout(Duration::from_secs(http.connect_timeout))
+            .timeout(Duration::from_secs(http.timeout));
+
+        // Without a proxy, reqwest uses the `HTTPS_PROXY`/`HTTP_PROXY` environment variables
+        if let Some(proxy) = &http.proxy {
+            builder = builder.proxy(reqwest::Proxy::all(proxy)?);
+        }
+
+        Ok(FigmaClient {
+            client: builder.build()?,
+            api_url: figma_config.api_url.trim_end_matches('/').to_string(),
+            token: figma_config.token.clone(),
+            retries: http.retries,
+            backoff: Duration::from_millis(http.backoff),
+        })
+    }
+
+    /// Sends a GET request, retrying with exponential backoff on rate limits, server and connection errors.
+    async fn send(&self, url: &str, with_token: bool) -> Result<Vec<u8>> {
+        let mut attempt: u32 = 0;
+
+        loop {
+            let wait = self
+                .backoff
+                .saturating_mul(2u32.saturating_pow(attempt))
+                .min(MAX_BACKOFF);
+            let retry = attempt < self.retries;
+            attempt += 1;
+
+            let mut request = self.client.get(url);
+            if with_token {
+                request = request.header("X-Figma-Token", self.token.expose());
+            }
+
+            let response = match request.send().await {
+                Ok(response) => response,
+                Err(e) if retry && (e.is_timeout() || e.is_connect()) => {
+                    tokio::time::sleep(wait).await;
+                    continue;
+                }
+                Err(e) => return Err(e.into()),
+            };
+
+            let status = response.status();
+
+            if status.is_success() {
+                return Ok(response.bytes().await?.to_vec());
+            }
+
+            match status {
+                StatusCode::TOO_MANY_REQUESTS => {
+                    let retry_after = response
+                        .headers()
+                        .get(RETRY_AFTER)
+                        .and_then(|v| v.to_str().ok())
+                        .and_then(|v| v.trim().parse::<u64>().ok());
+
+                    if !retry {
+                        return Err(Error::RateLimited(retry_after.unwrap_or(0)));
+                    }
+
+                    let wait = match retry_after {
+                        // A longer wait than the backoff limit would look like a hang
+                        Some(seconds) if Duration::from_secs(seconds) > MAX_BACKOFF => {
+                            return Err(Error::RateLimited(seconds))
+                        }
+                        Some(seconds) => Duration::from_secs(seconds),
+                        None => wait,
+                    };
+                    tokio::time::sleep(wait).await;
+                }
+                s if s.is_server_error() && retry => tokio::time::sleep(wait).await,
+                StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+                    return Err(Error::Unauthorized(status.as_u16()))
+                }
+                StatusCode::NOT_FOUND => return Err(Error::NotFound(url.to_string())),
+                _ => {
+                    let message = response.text().await.unwrap_or_default();
+                    return Err(Error::Http {
+                        status: status.as_u16(),
+                        message: self.token.redact(&message),
+                    });
+                }
+            }
+        }
+    }
+}
+
+impl HttpClient for FigmaClient {
+    async fn get(&self, path: &str) -> Result<Vec<u8>> {
+        self.send(&format!("{}{path}", self.api_url), true).await
+    }
+
+    async fn download(&self, url: &str) -> Result<Vec<u8>> {
+        self.send(url, false).await
+    }
+}
+
+/// The whole document is requested with `geometry=paths`, without it Figma leaves out the
+/// `relativeTransform` and `size` of the nodes
+pub fn file_path(file: &str, depth: Option<u32>) -> String {
+    match depth {
+        Some(depth) => format!("/files/{file}?depth={depth}"),
+        None => format!("/files/{file}?geometry=paths"),
+    }
+}
+
+pub fn nodes_path(file: &str, ids: &[String]) -> String {
+    format!("/files/{file}/nodes?ids={}&geometry=paths", ids.join(","))
+}
+
+pub fn image_fills_path(file: &str) -> String {
+    format!("/files/{file}/images")
+}
+
+pub fn images_path(file: &str, ids: &[String], format: ExportFormat, scale: f32) -> String {
+    format!(
+        "/images/{file}?ids={}&format={}&scale={scale}",
+        ids.join(","),
+        format.extension()
+    )
+}
+
+pub fn variables_path(file: &str) -> String {
+    format!("/files/{file}/variables/local")
+}
+
+/// Cheap request for the file version, without the pages content.
+pub async fn get_file_meta(client: &impl HttpClient, file: &str) -> Result<FileMeta> {
+    let meta = client.get(&file_path(file, Some(1))).await?;
+    Ok(serde_json::from_slice(&meta)?)
+}
+
+/// Local variables of the file, the endpoint is only open to the members of Enterprise organizations.
+pub async fn get_local_variables(client: &impl HttpClient, file: &str) -> Result<Variables> {
+    let variables = client.get(&variables_path(file)).await?;
+    let variables: LocalVariables = serde_json::from_slice(&variables)?;
+    Ok(variables.meta)
+}
+
+/// Fetches the pages and their top level nodes, then only the subtrees of the components that pass the filter
+/// and of the components they use.
+pub async fn get_partial_file(
+    client: &impl HttpClient,
+    file: &str,
+    filter: &Filter,
+) -> Result<FigmaData> {
+    let shallow = client.get(&file_path(file, Some(2))).await?;
+    let mut data: FigmaData = serde_json::from_slice(&shallow)?;
+
+    let mut ids: Vec<String> = Vec::new();
+    for page in data.document.common().children.iter() {
+        for node in page.common().children.iter() {
+            if node.is_component_or_set().is_some()
+                && filter.matches_page(page)
+                && filter.matches(node)
+            {
+                ids.push(node.common().id.clone());
+            }
+        }
+    }
+
+    let mut fetched: HashSet<String> = HashSet::new();
+
+    while !ids.is_empty() {
+        for chunk in ids.chunks(NODES_PER_REQUEST) {
+            let nodes = client.get(&nodes_path(file, chunk)).await?;
+            let nodes: FileNodes = serde_json::from_slice(&nodes)?;
+            data.merge_nodes(nodes);
+        }
+        fetched.extend(ids.drain(..));
+
+        // Instances can use components that didn't pass the filter, those are needed for their css
+        ids = missing_components(&data, &fetched);
+    }
+
+    Ok(data)
+}
+
+/// Renders the assets through the images endpoint, one request per format and scale, and saves them into `dir`.
+/// With `skip_existing` only the assets that are not on disk yet are downloaded.
+pub async fn download_assets(
+    client: &impl HttpClient,
+    file: &str,
+    assets: &[Asset],
+    dir: &Path,
+    skip_existing: bool,
+) -> Result<()> {
+    let mut batches: Vec<((ExportFormat, f32), Vec<&Asset>)> = Vec::new();
+
+    for asset in assets {
+        if skip_existing && dir.join(&asset.file_name).is_file() {
+            continue;
+        }
+
+        let key = (asset.format, asset.scale);
+        match batches.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, batch)) => batch.push(asset),
+            None => batches.push((key, vec![asset])),
+        }
+    }
+
+    if batches.is_empty() {
+        return Ok(());
+    }
+
+    std::fs::create_dir_all(dir)?;
+
+    for ((format, scale), batch) in batches {
+        for chunk in batch.chunks(NODES_PER_REQUEST) {
+            let mut ids: Vec<String> = chunk.iter().map(|a| a.node_id.clone()).collect();
+            ids.dedup();
+
+            let images = client.get(&images_path(file, &ids, format, scale)).await?;
+            let images: Images = serde_json::from_slice(&images)?;
+
+            if let Some(err) = images.err {
+                return Err(Error::Export(err));
+            }
+
+            for asset in chunk {
+                let Some(Some(url)) = images.images.get(&asset.node_id) else {
+                    return Err(Error::Export(format!(
+                        "node {} as {}",
+                        asset.node_id, asset.file_name
+                    )));
+                };
+
+                let content = client.download(url).await?;
+                std::fs::write(dir.join(&asset.file_name), content)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Downloads the images of the image fills into `dir`, named after their image ref.
+/// With `skip_existing` only the images that are not on disk yet are downloaded.
+pub async fn download_image_fills(
+    client: &impl HttpClient,
+    file: &str,
+    refs: &BTreeSet<String>,
+    dir: &Path,
+    skip_existing: bool,
+) -> Result<()> {
+    let existing = image_paths(dir);
+    let refs: Vec<&String> = refs
+        .iter()
+        .filter(|r| !skip_existing || !existing.contains_key(*r))
+        .collect();
+
+    if refs.is_empty() {
+        return Ok(());
+    }
+
+    let fills = client.get(&image_fills_path(file)).await?;
+    let fills: ImageFills = serde_json::from_slice(&fills)?;
+
+    if fills.error {
+        return Err(Error::Export("the image fills".to_string()));
+    }
+
+    let images_dir = dir.join(IMAGES_DIR);
+    std::fs::create_dir_all(&images_dir)?;
+
+    for image_ref in refs {
+        let Some(url) = fills.meta.images.get(image_ref) else {
+            return Err(Error::Export(format!("image fill {image_ref}")));
+        };
+
+        let content = client.download(url).await?;
+        let file_name = format!("{image_ref}.{}", image_extension(&content));
+        std::fs::write(images_dir.join(file_name), content)?;
+    }
+
+    Ok(())
+}
+
+fn missing_components(data: &FigmaData, fetched: &HashSet<String>) -> Vec<String> {
+    let mut instances: HashSet<String> = HashSet::new();
+
+    for page in data.document.common().children.iter() {
+        for node in page.common().children.iter() {
+            if fetched.contains(&node.common().id) {
+                collect_instances(node, &mut instances);
+            }
+        }
+    }
+
+    let mut ids: Vec<String> = instances
+        .into_iter()
+        .map(|id| match data.components.get(&id) {
+            Some(cmp) if !cmp.component_set_id.is_empty() => cmp.component_set_id.clone(),
+            _ => id,
+        })
+        .filter(|id| !fetched.contains(id) && is_top_level(data, id))
+        .collect();
+
+    ids.sort();
+    ids.dedup();
+    ids
+}
+
+fn collect_instances(node: &Node, instances: &mut HashSet<String>) {
+    if let Some((_, component_id)) = node.is_instance() {
+        instances.insert(component_id.clone());
+    }
+
+    for child in node.common().children.iter() {
+        collect_instances(child, instances);
+    }
+}
+
+fn is_top_level(data: &FigmaData, id: &String) -> bool {
+    data.document
+        .common()
+        .children
+        .iter()
+        .any(|page| page.common().children.iter().any(|n| &n.common().id == id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::HttpConfig;
+
+    fn client(api_url: String) -> FigmaClient {
+        FigmaClient::new(&FigmaConfig {
+            token: AccessToken::new("secret"),
+            file: "abc".to_string(),
+            partial: false,
+            api_url,
+            variables: false,
+            http: HttpConfig {
+                retries: 2,
+                backoff: 1,
+                ..HttpConfig::default()
+            },
+        })
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn status_errors() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/forbidden")
+            .with_status(403)
+            .with_body(r#"{"status":403,"err":"Invalid token"}"#)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/missing")
+            .with_status(404)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/bad")
+            .with_status(400)
+            .with_body("bad secret")
+            .create_async()
+            .await;
+
+        let client = client(server.url());
+
+        assert!(matches!(
+            client.get("/forbidden").await,
+            Err(Error::Unauthorized(403))
+        ));
+        assert!(matches!(
+            client.get("/missing").await,
+            Err(Error::NotFound(_))
+        ));
+        match client.get("/bad").await {
+            Err(Error::Http { status, message }) => {
+                assert_eq!(status, 400);
+                assert_eq!(message, "bad [REDACTED]");
+            }
+            other => panic!("unexpected result {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn long_retry_after() {
+        let mut server = mockito::Server::new_async().await;
+        let limited = server
+            .mock("GET", "/file")
+            .with_status(429)
+            .with_header("Retry-After", "86400")
+            .expect(1)
+            .create_async()
+            .await;
+
+        assert!(matches!(
+            client(server.url()).get("/file").await,
+            Err(Error::RateLimited(86400))
+        ));
+        limited.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn retries_rate_limit_and_server_errors() {
+        let mut server = mockito::Server::new_async().await;
+        let limited = server
+            .mock("GET", "/file")
+            .match_header("X-Figma-Token", "secret")
+            .with_status(429)
+            .with_header("Retry-After", "0")
+            .expect(1)
+            .create_async()
+            .await;
+        let unavailable = server
+            .mock("GET", "/file")
+            .with_status(503)
+            .expect(1)
+            .create_async()
+            .await;
+        let ok = server
+            .mock("GET", "/file")
+            .with_body("{}")
+            .expect(1)
+            .create_async()
+            .await;
+
+        let body = client(server.url()).get("/file").await.unwrap();
+
+        assert_eq!(body, b"{}");
+        limited.assert_async().await;
+        unavailable.assert_async().await;
+        ok.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn rate_limited_after_retries() {
+        let mut server = mockito::Server::new_async().await;
+        let limited = server
+            .mock("GET", "/file")
+            .with_status(429)
+            .with_header("Retry-After", "0")
+            .expect(3)
+            .create_async()
+            .await;
+
+        assert!(matches!(
+            client(server.url()).get("/file").await,
+            Err(Error::RateLimited(0))
+        ));
+        limited.assert_async().await;
+    }
+}
@@ -0,0 +1,195 @@
+//! Cache of the downloaded Figma files, one folder per file key:
+//! `{cache_dir}/cache/{file_key}/{cache.json, meta.json, original_output.json, variables.json}`
+
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::prelude::*;
+use crate::types::{file::FigmaData, variables::Variables};
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheMeta {
+    pub file_key: String,
+    pub version: String,
+    pub last_modified: String,
+    /// Only contains the subtrees fetched through the `/nodes` endpoint
+    #[serde(default)]
+    pub partial: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct Cache {
+    dir: PathBuf,
+}
+
+impl Cache {
+    pub fn new(cache_dir: PathBuf) -> Cache {
+        Cache {
+            dir: cache_dir.join("cache"),
+        }
+    }
+
+    /// Folder of the file, the key is checked so it can't point outside of the cache, ex: `../x`
+    fn file_dir(&self, file_key: &str) -> Result<PathBuf> {
+        if file_key.is_empty() || !file_key.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(Error::Cache {
+                path: self.dir.clone(),
+                message: format!("{file_key:?} is not a Figma file key, they are alphanumeric"),
+            });
+        }
+
+        Ok(self.dir.join(file_key))
+    }
+
+    pub fn data_file(&self, file_key: &str) -> Result<PathBuf> {
+        Ok(self.file_dir(file_key)?.join("cache.json"))
+    }
+
+    pub fn meta_file(&self, file_key: &str) -> Result<PathBuf> {
+        Ok(self.file_dir(file_key)?.join("meta.json"))
+    }
+
+    pub fn original_output_file(&self, file_key: &str) -> Result<PathBuf> {
+        Ok(self.file_dir(file_key)?.join("original_output.json"))
+    }
+
+    pub fn variables_file(&self, file_key: &str) -> Result<PathBuf> {
+        Ok(self.file_dir(file_key)?.join("variables.json"))
+    }
+
+    pub fn read(&self, file_key: &str) -> Result<FigmaData> {
+        let path = self.data_file(file_key)?;
+        let full_doc = std::fs::read(&path).map_err(|e| Error::Cache {
+            path,
+            message: e.to_string(),
+        })?;
+        Ok(serde_json::from_slice(&full_doc)?)
+    }
+
+    pub fn read_meta(&self, file_key: &str) -> Option<CacheMeta> {
+        let content = std::fs::read(self.meta_file(file_key).ok()?).ok()?;
+        serde_json::from_slice(&content).ok()
+    }
+
+    pub fn write(&self, file_key: &str, data: &FigmaData, partial: bool) -> Result<()> {
+        std::fs::create_dir_all(self.file_dir(file_key)?)?;
+
+        let meta = CacheMeta {
+            file_key: file_key.to_string(),
+            version: data.version.clone(),
+            last_modified: data.last_modified.clone(),
+            partial,
+        };
+
+        std::fs::write(
+            self.data_file(file_key)?,
+            serde_json::to_string_pretty(data)?,
+        )?;
+        std::fs::write(
+            self.meta_file(file_key)?,
+            serde_json::to_string_pretty(&meta)?,
+        )?;
+
+        Ok(())
+    }
+
+    pub fn write_original_output(&self, file_key: &str, content: &str) -> Result<()> {
+        std::fs::create_dir_all(self.file_dir(file_key)?)?;
+        std::fs::write(self.original_output_file(file_key)?, content)?;
+        Ok(())
+    }
+
+    /// Local variables of the file, when they were fetched
+    pub fn read_variables(&self, file_key: &str) -> Option<Variables> {
+        let content = std::fs::read(self.variables_file(file_key).ok()?).ok()?;
+        serde_json::from_slice(&content).ok()
+    }
+
+    pub fn write_variables(&self, file_key: &str, variables: &Variables) -> Result<()> {
+        std::fs::create_dir_all(self.file_dir(file_key)?)?;
+        std::fs::write(
+            self.variables_file(file_key)?,
+            serde_json::to_string_pretty(variables)?,
+        )?;
+        Ok(())
+    }
+
+    /// Key of the most recently written file, used when no file key is given.
+    pub fn latest(&self) -> Option<String> {
+        std::fs::read_dir(&self.dir)
+            .ok()?
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let modified = std::fs::metadata(entry.path().join("meta.json"))
+                    .and_then(|m| m.modified())
+                    .ok()?;
+                Some((modified, entry.file_name().to_string_lossy().to_string()))
+            })
+            .max()
+            .map(|(_, key)| key)
+    }
+
+    /// The cache can be used instead of downloading the file, when it's complete and the remote file didn't change.
+    pub fn is_fresh(&self, file_key: &str, version: &str, last_modified: &str) -> bool {
+        match self.read_meta(file_key) {
+            Some(meta) => {
+                !meta.partial
+                    && meta.version == version
+                    && meta.last_modified == last_modified
+                    && self.data_file(file_key).is_ok_and(|path| path.is_file())
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn figma_data(version: &str) -> FigmaData {
+        serde_json::from_str(&format!(
+            r#"{{
+                "name": "File", "lastModified": "2024-01-01T00:00:00Z", "version": "{version}",
+                "document": {{ "type": "DOCUMENT", "id": "0:0", "name": "Document" }},
+                "componentSets": {{}}, "components": {{}}, "styles": {{}}
+            }}"#
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn keyed_by_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::new(dir.path().to_path_buf());
+
+        cache.write("fileA", &figma_data("1"), false).unwrap();
+        cache.write("fileB", &figma_data("2"), true).unwrap();
+
+        assert_eq!(cache.read("fileA").unwrap().version, "1");
+        assert_eq!(cache.read("fileB").unwrap().version, "2");
+        assert!(cache.read("fileC").is_err());
+        assert_eq!(cache.latest().unwrap(), "fileB");
+
+        assert!(cache.is_fresh("fileA", "1", "2024-01-01T00:00:00Z"));
+        assert!(!cache.is_fresh("fileA", "2", "2024-01-01T00:00:00Z"));
+        assert!(!cache.is_fresh("fileA", "1", "2024-02-01T00:00:00Z"));
+        // Partial files are never reused, they may not contain all the components
+        assert!(!cache.is_fresh("fileB", "2", "2024-01-01T00:00:00Z"));
+    }
+
+    #[test]
+    fn file_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache::new(dir.path().join("out"));
+
+        for key in ["../../x", "a/b", ".", ""] {
+            let err = cache.write(key, &figma_data("1"), false).unwrap_err();
+            assert!(matches!(err, Error::Cache { .. }), "{key}");
+            assert!(cache.read(key).is_err());
+        }
+        assert!(!dir.path().join("x").exists());
+    }
+}
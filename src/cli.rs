@@ -1,22 +1,656 @@
-use clap::Parser;
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+};
 
-#[derive(Debug, Parser, Clone)]
+use clap::{Parser, Subcommand, ValueEnum};
+use serde::Deserialize;
+
+use crate::api::DEFAULT_API_URL;
+use crate::cache::Cache;
+use crate::config::ProjectConfig;
+use crate::prelude::*;
+use crate::utils::filter::Filter;
+
+pub const DEFAULT_OUT_DIR: &str = "figma_output";
+pub const DEFAULT_TOKEN_ENV: &str = "FIGMA_TOKEN";
+pub const DEFAULT_TAG_PREFIX: &str = "fg";
+pub const DEFAULT_ROOT_FONT_SIZE: f32 = 16.0;
+
+/// Figma access token, never printed by `Debug` or `Display`
+#[derive(Clone, PartialEq)]
+pub struct AccessToken(String);
+
+impl AccessToken {
+    pub fn new(token: &str) -> AccessToken {
+        AccessToken(token.trim().to_string())
+    }
+
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+
+    /// Replaces any occurrence of the token in `text`, so it can be safely logged or saved.
+    pub fn redact(&self, text: &str) -> String {
+        if self.0.is_empty() {
+            return text.to_string();
+        }
+        text.replace(&self.0, "[REDACTED]")
+    }
+}
+
+impl fmt::Debug for AccessToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("AccessToken([REDACTED])")
+    }
+}
+
+impl fmt::Display for AccessToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("[REDACTED]")
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct FigmaConfig {
     /// Figma access token
-    #[arg(short = 't', long = "token")]
-    pub token: String,
+    pub token: AccessToken,
     /// Figma file
     pub file: String,
+    /// Only fetch the component subtrees through the `/nodes` endpoint
+    pub partial: bool,
+    /// Base url of the Figma REST API
+    pub api_url: String,
+    /// Also fetch the local variables of the file
+    pub variables: bool,
+    pub http: HttpConfig,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpConfig {
+    /// Request timeout in seconds
+    pub timeout: u64,
+    /// Connection timeout in seconds
+    pub connect_timeout: u64,
+    /// Retries for rate limited, server and connection errors
+    pub retries: u32,
+    /// Initial wait between retries in milliseconds, doubled on every retry
+    pub backoff: u64,
+    /// Proxy url for all requests
+    pub proxy: Option<String>,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        HttpConfig {
+            timeout: 300,
+            connect_timeout: 30,
+            retries: 3,
+            backoff: 1000,
+            proxy: None,
+        }
+    }
+}
+
+/// Format of the generated components
+#[derive(Debug, Clone, Copy, Default, PartialEq, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Target {
+    /// Standalone `.html` preview of each component
+    #[default]
+    Html,
+    /// Custom element `.js` module of each component, with its styles in the Shadow DOM
+    WebComponents,
+    /// React `.tsx` component of each component, with its styles in a CSS module
+    React,
+    /// Vue single-file component of each component, with its styles scoped
+    Vue,
+    /// Svelte component of each component, with its styles in the component
+    Svelte,
+}
+
+/// Case of the custom property names
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum NameCase {
+    /// `--primary-background`
+    #[default]
+    Kebab,
+    /// `--primary_background`
+    Snake,
+    /// `--primaryBackground`
+    Camel,
+}
+
+/// Names of the custom properties of the tokens and variables
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Naming {
+    /// Added before the names, ex: `ds` for `--ds-primary-background`
+    pub prefix: Option<String>,
+    pub case: NameCase,
+}
+
+/// Unit of the lengths in the generated css
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LengthUnit {
+    #[default]
+    Px,
+    Rem,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Units {
+    pub length: LengthUnit,
+    /// Pixels in a `rem`
+    pub root_font_size: f32,
 }
 
+impl Default for Units {
+    fn default() -> Self {
+        Units {
+            length: LengthUnit::default(),
+            root_font_size: DEFAULT_ROOT_FONT_SIZE,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct OutputConfig {
+    /// Directory where the generated components and css are written
+    pub out_dir: PathBuf,
+    /// Directory for the cached files, defaults to the output directory
+    pub cache_dir: Option<PathBuf>,
+    pub target: Target,
+    /// Prefix of the custom element names, ex: `fg` for `<fg-button>`
+    pub tag_prefix: String,
+    pub naming: Naming,
+    pub units: Units,
+}
+
+impl OutputConfig {
+    pub fn cache_dir(&self) -> PathBuf {
+        match &self.cache_dir {
+            Some(dir) => dir.clone(),
+            None => self.out_dir.clone(),
+        }
+    }
+
+    pub fn cache(&self) -> Cache {
+        Cache::new(self.cache_dir())
+    }
+}
+
+/// CLI arguments merged with the project config file
+#[derive(Debug)]
+pub struct Settings {
+    pub command: Option<Command>,
+    pub figma_config: Option<FigmaConfig>,
+    pub output: OutputConfig,
+    pub filter: Filter,
+    /// File key used to read the cache when offline
+    pub cache_key: Option<String>,
+    /// Download the file even if the cache is up to date
+    pub refresh: bool,
+    /// Local variables JSON file, instead of the fetched variables
+    pub variables_file: Option<PathBuf>,
+}
+
+#[derive(Debug, Subcommand, Clone, PartialEq)]
+pub enum Command {
+    /// Download the Figma file and save it to the cache
+    Fetch {
+        /// Figma file
+        file: Option<String>,
+    },
+    /// Generate the components and tokens from the cache
+    Build,
+    /// Generate only the tokens (`css/theme.css`) from the cache
+    Tokens,
+    /// Print the node tree from the cache
+    Inspect {
+        /// Maximum depth of the tree to print
+        #[arg(long)]
+        depth: Option<usize>,
+    },
+}
+
+impl Command {
+    pub fn is_offline(&self) -> bool {
+        !matches!(self, Command::Fetch { .. })
+    }
+}
+
+/// Without a subcommand it fetches the file (or reads the cache) and builds everything in one pass.
 #[derive(Debug, Parser)]
 #[command(author, version, about, long_about = None)]
 pub struct Cli {
-    /// Optional Figma configuration
-    #[clap(flatten)]
-    pub figma_config: Option<FigmaConfig>,
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Figma access token, prefer `--token-file` or the `FIGMA_TOKEN` environment variable
+    #[arg(short = 't', long = "token", global = true)]
+    pub token: Option<String>,
+
+    /// File containing the Figma access token
+    #[arg(long = "token-file", conflicts_with = "token", global = true)]
+    pub token_file: Option<PathBuf>,
+
+    /// Figma file
+    pub file: Option<String>,
+
+    /// Directory where the generated components and css are written [default: figma_output]
+    #[arg(short = 'o', long = "out-dir", global = true)]
+    pub out_dir: Option<PathBuf>,
+
+    /// Directory for the cached files, defaults to the output directory
+    #[arg(long = "cache-dir", global = true)]
+    pub cache_dir: Option<PathBuf>,
+
+    /// Format of the generated components [default: html]
+    #[arg(long, value_enum, global = true)]
+    pub target: Option<Target>,
+
+    /// Prefix of the custom element names of the `web-components` target [default: fg]
+    #[arg(long = "tag-prefix", value_name = "PREFIX", global = true)]
+    pub tag_prefix: Option<String>,
+
+    /// Project config file, defaults to `fg-export.toml` or `fg-export.json` in the current directory
+    #[arg(short = 'c', long = "config", global = true)]
+    pub config: Option<PathBuf>,
+
+    /// Only generate components matching the name, `*` and `?` wildcards are supported
+    #[arg(long = "component", value_name = "NAME", global = true)]
+    pub components: Vec<String>,
+
+    /// Only generate the component with this node id, ex: `123:45`
+    #[arg(long = "node", value_name = "ID", global = true)]
+    pub nodes: Vec<String>,
+
+    /// Only generate components from the matching pages, `*` and `?` wildcards are supported
+    #[arg(long = "page", value_name = "NAME", global = true)]
+    pub pages: Vec<String>,
+
+    /// Fetch the shallow file and then only the needed component subtrees, instead of the whole document
+    #[arg(long, global = true)]
+    pub partial: bool,
+
+    /// Base url of the Figma REST API [default: https://api.figma.com/v1]
+    #[arg(long = "api-url", global = true)]
+    pub api_url: Option<String>,
+
+    /// Request timeout in seconds [default: 300]
+    #[arg(long, value_name = "SECONDS", global = true)]
+    pub timeout: Option<u64>,
+
+    /// Retries for rate limited, server and connection errors [default: 3]
+    #[arg(long, global = true)]
+    pub retries: Option<u32>,
+
+    /// Proxy url for the Figma requests, defaults to the `HTTPS_PROXY` environment variable
+    #[arg(long, global = true)]
+    pub proxy: Option<String>,
+
+    /// Fetch the local variables of the file, the endpoint needs an Enterprise plan
+    #[arg(long = "fetch-variables", global = true)]
+    pub fetch_variables: bool,
+
+    /// JSON file with the local variables, as returned by `/v1/files/:key/variables/local`
+    #[arg(long, value_name = "FILE", global = true)]
+    pub variables: Option<PathBuf>,
+
+    /// If set, don't connect to the network, but use the cache of the file key, or the most recent one
+    #[arg(
+        long,
+        value_name = "FILE_KEY",
+        num_args = 0..=1,
+        global = true,
+        conflicts_with_all = &["token", "token_file", "refresh"]
+    )]
+    pub cache: Option<Option<String>>,
+
+    /// Download the file even if the cached version is up to date
+    #[arg(long, global = true)]
+    pub refresh: bool,
+}
+
+impl Cli {
+    /// Reads the project config file and applies the CLI flags on top of it.
+    pub fn settings(self) -> Result<Settings> {
+        let config = ProjectConfig::load(self.config.as_deref())?.unwrap_or_default();
+
+        let output = OutputConfig {
+            out_dir: self
+                .out_dir
+                .or(config.output.out_dir)
+                .unwrap_or_else(|| PathBuf::from(DEFAULT_OUT_DIR)),
+            cache_dir: self.cache_dir.or(config.output.cache_dir),
+            target: self.target.or(config.output.target).unwrap_or_default(),
+            tag_prefix: self
+                .tag_prefix
+                .or(config.output.tag_prefix)
+                .unwrap_or_else(|| DEFAULT_TAG_PREFIX.to_string()),
+            naming: Naming {
+                prefix: config.naming.prefix,
+                case: config.naming.case.unwrap_or_default(),
+            },
+            units: Units {
+                length: config.units.length.unwrap_or_default(),
+                root_font_size: config
+                    .units
+                    .root_font_size
+                    .unwrap_or(DEFAULT_ROOT_FONT_SIZE),
+            },
+        };
+
+        if !is_tag_prefix(&output.tag_prefix) {
+            return Err(Error::TagPrefix(output.tag_prefix));
+        }
+
+        let filter = Filter {
+            components: or_config(self.components, config.filter.components),
+            nodes: or_config(self.nodes, config.filter.nodes),
+            pages: or_config(self.pages, config.filter.pages),
+        };
+
+        let default_http = HttpConfig::default();
+        let http = HttpConfig {
+            timeout: self
+                .timeout
+                .or(config.fetch.timeout)
+                .unwrap_or(default_http.timeout),
+            retries: self
+                .retries
+                .or(config.fetch.retries)
+                .unwrap_or(default_http.retries),
+            proxy: self.proxy.or(config.fetch.proxy),
+            ..default_http
+        };
+
+        let offline =
+            self.cache.is_some() || self.command.as_ref().is_some_and(Command::is_offline);
+
+        let file = match &self.command {
+            Some(Command::Fetch { file: Some(file) }) => Some(file.clone()),
+            _ => self.file,
+        }
+        .or(config.file);
+
+        let cache_key = match self.cache {
+            Some(Some(key)) => Some(key),
+            _ => file.clone(),
+        };
+
+        let figma_config = if offline {
+            None
+        } else {
+            match file {
+                Some(file) => {
+                    let token = resolve_token(
+                        self.token,
+                        self.token_file.or(config.token_file).as_deref(),
+                        config.token_env.as_deref().unwrap_or(DEFAULT_TOKEN_ENV),
+                    )?;
+                    Some(FigmaConfig {
+                        token,
+                        file,
+                        partial: self.partial || config.fetch.partial,
+                        api_url: self
+                            .api_url
+                            .or(config.fetch.api_url)
+                            .unwrap_or_else(|| DEFAULT_API_URL.to_string()),
+                        variables: self.fetch_variables || config.fetch.variables,
+                        http,
+                    })
+                }
+                None if self.command.is_some() => return Err(Error::MissingFile),
+                None => None,
+            }
+        };
+
+        Ok(Settings {
+            command: self.command,
+            figma_config,
+            output,
+            filter,
+            cache_key,
+            refresh: self.refresh,
+            variables_file: self.variables.or(config.variables),
+        })
+    }
+}
+
+fn or_config(values: Vec<String>, config: Vec<String>) -> Vec<String> {
+    if values.is_empty() {
+        config
+    } else {
+        values
+    }
+}
+
+/// Custom element names must start with a lowercase letter, the prefix adds the required hyphen
+fn is_tag_prefix(prefix: &str) -> bool {
+    prefix.starts_with(|c: char| c.is_ascii_lowercase())
+        && prefix
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
+
+/// Looks for the access token in the `--token` flag, then the token file and finally the environment variable.
+fn resolve_token(
+    token: Option<String>,
+    token_file: Option<&Path>,
+    token_env: &str,
+) -> Result<AccessToken> {
+    let mut tried: Vec<String> = vec!["--token".to_string()];
+
+    if let Some(token) = token {
+        return Ok(AccessToken::new(&token));
+    }
+
+    if let Some(path) = token_file {
+        let content = std::fs::read_to_string(path).map_err(|e| Error::TokenFile {
+            path: path.to_path_buf(),
+            message: e.to_string(),
+        })?;
+        let token = AccessToken::new(&content);
+        if !token.expose().is_empty() {
+            return Ok(token);
+        }
+        tried.push(format!("token file {}", path.display()));
+    }
+
+    if let Ok(token) = std::env::var(token_env) {
+        let token = AccessToken::new(&token);
+        if !token.expose().is_empty() {
+            return Ok(token);
+        }
+    }
+    tried.push(format!("${token_env}"));
+
+    Err(Error::MissingToken(tried.join(", ")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn naming_and_units_from_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("fg-export.toml");
+        std::fs::write(
+            &path,
+            "[naming]\nprefix = \"ds\"\ncase = \"camel\"\n[units]\nlength = \"rem\"\n",
+        )
+        .unwrap();
+
+        let settings = Cli::parse_from(["fg-export", "-c", path.to_str().unwrap(), "--cache"])
+            .settings()
+            .unwrap();
+        assert_eq!(
+            settings.output.naming,
+            Naming {
+                prefix: Some("ds".to_string()),
+                case: NameCase::Camel,
+            }
+        );
+        assert_eq!(
+            settings.output.units,
+            Units {
+                length: LengthUnit::Rem,
+                root_font_size: DEFAULT_ROOT_FONT_SIZE,
+            }
+        );
+    }
+
+    #[test]
+    fn cli_overrides_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("fg-export.toml");
+        std::fs::write(
+            &path,
+            "file = \"from-config\"\n[output]\nout-dir = \"config_out\"\ncache-dir = \"config_cache\"\n",
+        )
+        .unwrap();
+        let config = path.to_str().unwrap();
+
+        let settings = Cli::parse_from(["fg-export", "-c", config, "-t", "secret"])
+            .settings()
+            .unwrap();
+        assert_eq!(settings.figma_config.unwrap().file, "from-config");
+        assert_eq!(settings.output.out_dir, PathBuf::from("config_out"));
+        assert_eq!(settings.output.cache_dir(), PathBuf::from("config_cache"));
+
+        let settings = Cli::parse_from([
+            "fg-export",
+            "-c",
+            config,
+            "-t",
+            "secret",
+            "-o",
+            "cli_out",
+            "from-cli",
+        ])
+        .settings()
+        .unwrap();
+        assert_eq!(settings.figma_config.unwrap().file, "from-cli");
+        assert_eq!(settings.output.out_dir, PathBuf::from("cli_out"));
+
+        let settings = Cli::parse_from(["fg-export", "-c", config, "--cache"])
+            .settings()
+            .unwrap();
+        assert!(settings.figma_config.is_none());
+        assert_eq!(settings.cache_key.as_deref(), Some("from-config"));
+
+        let settings = Cli::parse_from(["fg-export", "-c", config, "build", "--cache", "other"])
+            .settings()
+            .unwrap();
+        assert!(settings.figma_config.is_none());
+        assert_eq!(settings.cache_key.as_deref(), Some("other"));
+    }
+
+    #[test]
+    fn token_sources() {
+        let dir = tempfile::tempdir().unwrap();
+        let token_file = dir.path().join("token");
+        std::fs::write(&token_file, "file-token\n").unwrap();
+
+        let token = resolve_token(None, Some(&token_file), "FG_EXPORT_TEST_UNSET").unwrap();
+        assert_eq!(token.expose(), "file-token");
+
+        std::env::set_var("FG_EXPORT_TEST_TOKEN", "env-token");
+        let token = resolve_token(None, None, "FG_EXPORT_TEST_TOKEN").unwrap();
+        assert_eq!(token.expose(), "env-token");
+
+        let token = resolve_token(Some("flag-token".to_string()), None, "FG_EXPORT_TEST_TOKEN");
+        assert_eq!(token.unwrap().expose(), "flag-token");
+
+        let err = resolve_token(None, None, "FG_EXPORT_TEST_UNSET")
+            .unwrap_err()
+            .to_string();
+        assert_eq!(
+            err,
+            "Missing Figma access token, tried: --token, $FG_EXPORT_TEST_UNSET"
+        );
+    }
+
+    #[test]
+    fn token_is_redacted() {
+        let token = AccessToken::new("figd_secret");
+        let config = FigmaConfig {
+            token: token.clone(),
+            file: "abc".to_string(),
+            partial: false,
+            api_url: DEFAULT_API_URL.to_string(),
+            variables: false,
+            http: HttpConfig::default(),
+        };
+
+        assert!(!format!("{config:?}").contains("figd_secret"));
+        assert_eq!(token.to_string(), "[REDACTED]");
+        assert_eq!(
+            token.redact("{\"token\":\"figd_secret\"}"),
+            "{\"token\":\"[REDACTED]\"}"
+        );
+    }
+
+    #[test]
+    fn subcommands() {
+        let settings = Cli::parse_from(["fg-export", "fetch", "abc", "-t", "secret", "-o", "out"])
+            .settings()
+            .unwrap();
+        assert_eq!(
+            settings.command,
+            Some(Command::Fetch {
+                file: Some("abc".to_string())
+            })
+        );
+        assert_eq!(settings.figma_config.unwrap().file, "abc");
+        assert_eq!(settings.output.out_dir, PathBuf::from("out"));
+
+        let settings = Cli::parse_from(["fg-export", "-t", "secret", "build"])
+            .settings()
+            .unwrap();
+        assert_eq!(settings.command, Some(Command::Build));
+        assert!(settings.figma_config.is_none());
+
+        let settings = Cli::parse_from(["fg-export", "inspect", "--depth", "2"])
+            .settings()
+            .unwrap();
+        assert_eq!(settings.command, Some(Command::Inspect { depth: Some(2) }));
+
+        let settings = Cli::parse_from(["fg-export", "-t", "secret", "abc"])
+            .settings()
+            .unwrap();
+        assert_eq!(settings.command, None);
+        assert_eq!(settings.figma_config.unwrap().file, "abc");
+    }
+
+    #[test]
+    fn filters() {
+        let settings = Cli::parse_from([
+            "fg-export",
+            "build",
+            "--component",
+            "Button*",
+            "--component",
+            "Input",
+            "--node",
+            "123:45",
+            "--page",
+            "Forms",
+        ])
+        .settings()
+        .unwrap();
 
-    /// If set, don't connect to the network, but use the `figma_output/cache.json`
-    #[arg(long, conflicts_with_all = &["token", "file"])]
-    pub cache: bool,
+        assert_eq!(
+            settings.filter,
+            Filter {
+                components: vec!["Button*".to_string(), "Input".to_string()],
+                nodes: vec!["123:45".to_string()],
+                pages: vec!["Forms".to_string()],
+            }
+        );
+    }
 }
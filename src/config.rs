@@ -0,0 +1,265 @@
+//! Project configuration, read from `fg-export.toml` or `fg-export.json`
+
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::cli::{LengthUnit, NameCase, Target};
+use crate::prelude::*;
+
+pub const CONFIG_FILES: [&str; 2] = ["fg-export.toml", "fg-export.json"];
+
+#[derive(Debug, Deserialize, Default, Clone, PartialEq)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct ProjectConfig {
+    /// Figma file key
+    pub file: Option<String>,
+    /// Name of the environment variable holding the Figma access token, defaults to `FIGMA_TOKEN`
+    pub token_env: Option<String>,
+    /// File containing the Figma access token
+    pub token_file: Option<PathBuf>,
+    /// JSON file with the local variables, as returned by `/v1/files/:key/variables/local`
+    pub variables: Option<PathBuf>,
+    #[serde(default)]
+    pub output: OutputSection,
+    #[serde(default)]
+    pub filter: FilterSection,
+    #[serde(default)]
+    pub fetch: FetchSection,
+    #[serde(default)]
+    pub naming: NamingSection,
+    #[serde(default)]
+    pub units: UnitsSection,
+}
+
+#[derive(Debug, Deserialize, Default, Clone, PartialEq)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct OutputSection {
+    pub out_dir: Option<PathBuf>,
+    pub cache_dir: Option<PathBuf>,
+    /// Format of the generated components, `html`, `web-components`, `react`, `vue` or `svelte`
+    pub target: Option<Target>,
+    /// Prefix of the custom element names
+    pub tag_prefix: Option<String>,
+}
+
+/// Names of the custom properties of the tokens and variables
+#[derive(Debug, Deserialize, Default, Clone, PartialEq)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct NamingSection {
+    /// Added before the names, ex: `ds` for `--ds-primary-background`
+    pub prefix: Option<String>,
+    /// `kebab`, `snake` or `camel`
+    pub case: Option<NameCase>,
+}
+
+/// Units of the generated css
+#[derive(Debug, Deserialize, Default, Clone, PartialEq)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct UnitsSection {
+    /// Unit of the lengths, `px` or `rem`
+    pub length: Option<LengthUnit>,
+    /// Pixels in a `rem`, defaults to 16
+    pub root_font_size: Option<f32>,
+}
+
+#[derive(Debug, Deserialize, Default, Clone, PartialEq)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct FilterSection {
+    #[serde(default)]
+    pub components: Vec<String>,
+    #[serde(default)]
+    pub nodes: Vec<String>,
+    #[serde(default)]
+    pub pages: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Default, Clone, PartialEq)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct FetchSection {
+    /// Fetch the shallow file and then only the needed component subtrees
+    #[serde(default)]
+    pub partial: bool,
+    /// Base url of the Figma REST API
+    pub api_url: Option<String>,
+    /// Request timeout in seconds
+    pub timeout: Option<u64>,
+    /// Retries for rate limited, server and connection errors
+    pub retries: Option<u32>,
+    /// Proxy url for the Figma requests
+    pub proxy: Option<String>,
+    /// Fetch the local variables, only available with an Enterprise plan
+    #[serde(default)]
+    pub variables: bool,
+}
+
+impl ProjectConfig {
+    /// Loads the given config file, or looks for one of `CONFIG_FILES` in the current directory.
+    pub fn load(path: Option<&Path>) -> Result<Option<ProjectConfig>> {
+        let path = match path {
+            Some(p) => p.to_path_buf(),
+            None => match CONFIG_FILES.iter().map(PathBuf::from).find(|p| p.is_file()) {
+                Some(p) => p,
+                None => return Ok(None),
+            },
+        };
+
+        let content = std::fs::read_to_string(&path).map_err(|e| Error::Config {
+            path: path.clone(),
+            message: e.to_string(),
+        })?;
+
+        let config = ProjectConfig::from_str(&path, &content)?;
+
+        Ok(Some(config))
+    }
+
+    fn from_str(path: &Path, content: &str) -> Result<ProjectConfig> {
+        let config: ProjectConfig = match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => serde_json::from_str(content).map_err(|e| e.to_string()),
+            _ => toml::from_str(content).map_err(|e| e.message().to_string()),
+        }
+        .map_err(|message| Error::Config {
+            path: path.to_path_buf(),
+            message,
+        })?;
+
+        config.validate().map_err(|message| Error::Config {
+            path: path.to_path_buf(),
+            message,
+        })?;
+
+        Ok(config)
+    }
+
+    fn validate(&self) -> core::result::Result<(), String> {
+        if let Some(file) = &self.file {
+            if file.trim().is_empty() {
+                return Err("`file` can't be empty".to_string());
+            }
+        }
+
+        if let Some(env) = &self.token_env {
+            if env.trim().is_empty() || env.contains('=') {
+                return Err(format!("`token-env` is not a valid variable name: {env:?}"));
+            }
+        }
+
+        if let Some(prefix) = &self.naming.prefix {
+            let is_name = |c: char| c.is_ascii_alphanumeric() || c == '-' || c == '_';
+            if prefix.is_empty() || !prefix.chars().all(is_name) {
+                return Err(format!(
+                    "`naming.prefix` is not a valid custom property name: {prefix:?}"
+                ));
+            }
+        }
+
+        if let Some(size) = self.units.root_font_size {
+            if size <= 0.0 {
+                return Err("`units.root-font-size` must be positive".to_string());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_toml() {
+        let config = ProjectConfig::from_str(
+            Path::new("fg-export.toml"),
+            r#"
+            file = "abc123"
+            token-env = "MY_FIGMA_TOKEN"
+
+            [output]
+            out-dir = "packages/ds"
+            target = "web-components"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.file.as_deref(), Some("abc123"));
+        assert_eq!(config.token_env.as_deref(), Some("MY_FIGMA_TOKEN"));
+        assert_eq!(config.output.out_dir, Some(PathBuf::from("packages/ds")));
+        assert_eq!(config.output.cache_dir, None);
+        assert_eq!(config.output.target, Some(Target::WebComponents));
+    }
+
+    #[test]
+    fn naming_and_units() {
+        let config = ProjectConfig::from_str(
+            Path::new("fg-export.toml"),
+            r#"
+            [naming]
+            prefix = "ds"
+            case = "snake"
+
+            [units]
+            length = "rem"
+            root-font-size = 10
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.naming.prefix.as_deref(), Some("ds"));
+        assert_eq!(config.naming.case, Some(NameCase::Snake));
+        assert_eq!(config.units.length, Some(LengthUnit::Rem));
+        assert_eq!(config.units.root_font_size, Some(10.0));
+
+        let err = ProjectConfig::from_str(
+            Path::new("fg-export.json"),
+            r#"{ "naming": { "prefix": "d s" } }"#,
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("`naming.prefix` is not a valid custom property name"));
+
+        let err =
+            ProjectConfig::from_str(Path::new("fg-export.toml"), "[units]\nroot-font-size = 0")
+                .unwrap_err()
+                .to_string();
+        assert!(err.contains("`units.root-font-size` must be positive"));
+    }
+
+    #[test]
+    fn parse_json() {
+        let config = ProjectConfig::from_str(
+            Path::new("fg-export.json"),
+            r#"{ "file": "abc123", "output": { "cache-dir": ".cache" } }"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.file.as_deref(), Some("abc123"));
+        assert_eq!(config.output.cache_dir, Some(PathBuf::from(".cache")));
+    }
+
+    #[test]
+    fn unknown_keys() {
+        let err = ProjectConfig::from_str(Path::new("fg-export.toml"), "fil = \"abc\"")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("fg-export.toml"));
+        assert!(err.contains("unknown field `fil`"));
+
+        let err = ProjectConfig::from_str(
+            Path::new("fg-export.json"),
+            r#"{ "output": { "outdir": "x" } }"#,
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("unknown field `outdir`"));
+    }
+
+    #[test]
+    fn invalid_values() {
+        let err = ProjectConfig::from_str(Path::new("fg-export.toml"), "file = \" \"")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("`file` can't be empty"));
+    }
+}
@@ -19,6 +19,54 @@ pub enum Error {
     #[error(transparent)]
     Json(#[from] serde_json::Error),
 
+    #[error("Figma rejected the access token (status {0}), check that it is valid and has access to the file")]
+    Unauthorized(u16),
+
+    #[error("Figma file or node not found: {0}")]
+    NotFound(String),
+
+    #[error("Figma rate limit reached, retry after {0} seconds")]
+    RateLimited(u64),
+
+    #[error("Figma request failed with status {status}: {message}")]
+    Http { status: u16, message: String },
+
+    #[error("Figma could not export {0}")]
+    Export(String),
+
     #[error("File does not contain any components!!")]
     NoComponent,
+
+    #[error("Invalid config file {}: {message}", path.display())]
+    Config {
+        path: std::path::PathBuf,
+        message: String,
+    },
+
+    #[error("Can't read cache {}: {message}", path.display())]
+    Cache {
+        path: std::path::PathBuf,
+        message: String,
+    },
+
+    #[error("Missing Figma file key, pass it as an argument or set `file` in the config file")]
+    MissingFile,
+
+    #[error("Invalid tag prefix {0:?}, it must start with a lowercase letter and only contain lowercase letters, digits and hyphens")]
+    TagPrefix(String),
+
+    #[error("Missing Figma access token, tried: {0}")]
+    MissingToken(String),
+
+    #[error("Can't read variables file {}: {message}", path.display())]
+    Variables {
+        path: std::path::PathBuf,
+        message: String,
+    },
+
+    #[error("Can't read token file {}: {message}", path.display())]
+    TokenFile {
+        path: std::path::PathBuf,
+        message: String,
+    },
 }
@@ -0,0 +1,63 @@
+//! Standalone preview page of each component, with the css of the components it uses
+
+use std::collections::HashSet;
+
+use askama::Template;
+
+use super::{Component, Element, Generator, Library};
+
+#[derive(Template, Debug)]
+#[template(path = "component.html", escape = "none")]
+struct ComponentTemplate {
+    name: String,
+    styles: String,
+    markup: String,
+    tokens: String,
+}
+
+pub struct Html;
+
+impl Generator for Html {
+    fn generate(&self, component: &Component, library: &Library) -> Vec<(String, String)> {
+        // The instances of other components are inlined, so their css is needed too
+        let mut css: Vec<&str> = library
+            .dependencies(&component.id)
+            .into_iter()
+            .map(|dependency| dependency.css.as_str())
+            .collect();
+        css.push(&component.css);
+
+        let page = ComponentTemplate {
+            name: component.name.clone(),
+            styles: css.join(""),
+            markup: create_markup(&component.markup, component.is_set),
+            tokens: library.theme(),
+        };
+
+        vec![("html".to_string(), page.render().unwrap())]
+    }
+
+    /// The pages inline the components they use
+    fn generates_dependencies(&self) -> bool {
+        false
+    }
+}
+
+fn create_markup(values: &[Element], is_set: bool) -> String {
+    let mut content = values[0].render().unwrap();
+
+    if is_set {
+        content = String::new();
+        let mut seen = HashSet::new();
+
+        // Remove duplicate markup, the variants only differing by pseudo-classes have the same
+        for child in &values[0].children {
+            let markup = child.render().unwrap();
+            if seen.insert(markup.clone()) {
+                content.push_str(&format!("{markup}\n"));
+            }
+        }
+    }
+
+    content
+}
@@ -0,0 +1,365 @@
+//! Output formats of the components. The traversal of the Figma nodes builds an intermediate
+//! representation of each component, its markup tree with the attributes and css rules of each
+//! node and the variants, instances and texts marked, then a `Generator` turns it into the files of a target.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use askama::Template;
+use convert_case::{Case, Casing};
+
+use crate::{
+    cli::{Target, Units},
+    types::{token::Token, variables::Variables},
+    utils::parse_components::theme_css,
+};
+
+pub mod html;
+pub mod react;
+pub mod svelte;
+pub mod vue;
+pub mod web_components;
+
+/// Output format of the components
+pub trait Generator {
+    /// Files of the component in its `components/<name>/` folder by extension,
+    /// ex: `("module.css", css)` is written to `button.module.css`
+    fn generate(&self, component: &Component, library: &Library) -> Vec<(String, String)>;
+
+    /// The components used by the selected ones are generated too, for the targets that import them
+    fn generates_dependencies(&self) -> bool {
+        true
+    }
+}
+
+impl Target {
+    pub fn generator(&self, tag_prefix: &str) -> Box<dyn Generator> {
+        match self {
+            Target::Html => Box::new(html::Html),
+            Target::WebComponents => Box::new(web_components::WebComponents {
+                tag_prefix: tag_prefix.to_string(),
+            }),
+            Target::React => Box::new(react::React),
+            Target::Vue => Box::new(vue::Vue),
+            Target::Svelte => Box::new(svelte::Svelte),
+        }
+    }
+}
+
+/// Element of the markup tree, rendered as HTML by the `markup.html` template
+#[derive(Template, Debug, Default, Clone, PartialEq, Eq, Hash)]
+#[template(path = "markup.html", escape = "none")]
+pub struct Element {
+    pub tag: String,
+    /// Attributes in order, ex: `[("class", "button"), ("size", "small")]`, the ones with an empty
+    /// value are rendered without it
+    pub attributes: Vec<(String, String)>,
+    /// Selector of the rules, ex: `.card .title`
+    pub selector: String,
+    /// Css declarations of the node by property, the tokens are `var()` references to the theme
+    pub rules: BTreeMap<String, String>,
+    pub characters: String,
+    pub children: Vec<Element>,
+    /// Variant attributes of the frame, ex: `[("size", "large")]`
+    pub variant: Vec<(String, String)>,
+    /// Name of the text layer, for the targets where texts are slots
+    pub slot: Option<String>,
+    /// Id of the component or set, when the element is an instance of it
+    pub component: Option<String>,
+}
+
+/// Component or component set, with the css of its nodes
+#[derive(Debug, Clone)]
+pub struct Component {
+    /// Node id of the component or set
+    pub id: String,
+    /// Name of the files and elements, ex: `button`
+    pub name: String,
+    /// Minified css of the rules of its nodes
+    pub css: String,
+    /// Markup of the component, the children of a set's root are its variants
+    pub markup: Vec<Element>,
+    pub is_set: bool,
+    /// Ids of the components of its instances
+    pub includes: Vec<String>,
+}
+
+/// Every generated component, with the tokens
+pub struct Library<'a> {
+    pub components: &'a BTreeMap<String, Component>,
+    /// Tokens of the styles by style id
+    pub tokens: &'a HashMap<String, Token>,
+    /// Variables of the file, their modes are themes
+    pub variables: &'a Variables,
+    pub units: &'a Units,
+}
+
+impl Library<'_> {
+    /// Custom properties of the tokens and variables, the content of `css/theme.css`
+    pub fn theme(&self) -> String {
+        theme_css(self.tokens, self.variables, self.units)
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Component> {
+        self.components.get(id)
+    }
+
+    /// Components used by the component, including the ones of nested instances, in the order their css should be added.
+    pub fn dependencies(&self, id: &str) -> Vec<&Component> {
+        let mut includes: Vec<&String> = Vec::new();
+        let mut stack: Vec<&String> = match self.get(id) {
+            Some(component) => component.includes.iter().rev().collect(),
+            None => Vec::new(),
+        };
+
+        while let Some(include) = stack.pop() {
+            if include == id || includes.contains(&include) {
+                continue;
+            }
+
+            includes.push(include);
+
+            if let Some(component) = self.get(include) {
+                stack.extend(component.includes.iter().rev());
+            }
+        }
+
+        includes.into_iter().filter_map(|id| self.get(id)).collect()
+    }
+
+    /// Components of the instances in the rendered `elements`, without the ones nested in other
+    /// instances, those are rendered by their own component
+    pub fn imports(&self, elements: &[&Element]) -> Vec<&Component> {
+        let mut imports: Vec<&Component> = Vec::new();
+        let mut stack: Vec<&Element> = elements.iter().rev().copied().collect();
+
+        while let Some(element) = stack.pop() {
+            match element.component.as_ref().and_then(|id| self.get(id)) {
+                Some(component) if !imports.iter().any(|c| c.id == component.id) => {
+                    imports.push(component)
+                }
+                Some(_) => {}
+                None => stack.extend(element.children.iter().rev()),
+            }
+        }
+
+        imports
+    }
+}
+
+impl Component {
+    /// Markup of each variant, the children of a set with the same attributes only differ by pseudo-classes
+    pub fn variants(&self) -> Vec<&Element> {
+        let elements: Vec<&Element> = if self.is_set {
+            self.markup.iter().flat_map(|m| m.children.iter()).collect()
+        } else {
+            self.markup.iter().take(1).collect()
+        };
+        let mut seen = HashSet::new();
+
+        elements
+            .into_iter()
+            .filter(|element| seen.insert(&element.variant))
+            .collect()
+    }
+
+    /// Values of each variant property, in the order of the variants so the first one is the default
+    pub fn variant_properties(&self) -> Vec<(String, Vec<String>)> {
+        let mut properties: Vec<(String, Vec<String>)> = Vec::new();
+
+        for element in self.variants() {
+            for (attribute, value) in element.variant.iter() {
+                let value = prop_value(value);
+                match properties.iter_mut().find(|(a, _)| a == attribute) {
+                    Some((_, values)) if !values.contains(&value) => values.push(value),
+                    Some(_) => {}
+                    None => properties.push((attribute.clone(), vec![value])),
+                }
+            }
+        }
+
+        properties
+    }
+}
+
+impl Element {
+    /// Attributes of the markup, ex: ` class="button" size="small"`
+    pub fn html_attributes(&self) -> String {
+        self.attributes
+            .iter()
+            .map(|(name, value)| {
+                if value.is_empty() {
+                    format!(" {name}")
+                } else {
+                    format!(" {name}=\"{}\"", escape_attribute(value))
+                }
+            })
+            .collect()
+    }
+
+    /// Copy of the markup with the characters of the texts escaped by `escape`
+    pub fn escaped(&self, escape: &impl Fn(&str) -> String) -> Element {
+        Element {
+            characters: escape(&self.characters),
+            children: self
+                .children
+                .iter()
+                .map(|child| child.escaped(escape))
+                .collect(),
+            ..self.clone()
+        }
+    }
+
+    /// Name and characters of the texts, without the ones of the nested instances
+    pub fn texts(&self) -> Vec<(String, String)> {
+        let mut texts = Vec::new();
+        self.collect_texts(&mut texts);
+        texts
+    }
+
+    /// Texts become slots with their characters as fallback content, and the instances of the
+    /// generated components are replaced by `instance`, ex: their custom element
+    pub fn slotted(
+        &self,
+        library: &Library,
+        instance: &impl Fn(&Component, &Element) -> Element,
+    ) -> Element {
+        if let Some(slot) = &self.slot {
+            return Element {
+                characters: format!("<slot name=\"{slot}\">{}</slot>", self.characters),
+                ..self.clone()
+            };
+        }
+
+        if let Some(component) = self.component.as_ref().and_then(|id| library.get(id)) {
+            return instance(component, self);
+        }
+
+        Element {
+            children: self
+                .children
+                .iter()
+                .map(|child| child.slotted(library, instance))
+                .collect(),
+            ..self.clone()
+        }
+    }
+
+    fn collect_texts(&self, texts: &mut Vec<(String, String)>) {
+        for child in self.children.iter() {
+            match &child.slot {
+                Some(slot) if !texts.iter().any(|(s, _)| s == slot) => {
+                    texts.push((slot.clone(), child.characters.clone()))
+                }
+                Some(_) => {}
+                None if child.component.is_some() => {}
+                None => child.collect_texts(texts),
+            }
+        }
+    }
+}
+
+/// Value of a variant property, the `default` variants have an empty attribute
+pub fn prop_value(value: &str) -> String {
+    if value.is_empty() {
+        "default".to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+/// JS string literal of the value, ex: `"large"`
+pub fn json(value: &str) -> String {
+    serde_json::Value::from(value).to_string()
+}
+
+/// Value of an HTML attribute, between double quotes
+pub fn escape_attribute(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Text of the markup of the targets compiling templates, the braces are entities so they aren't
+/// read as expressions, ex: `{count}` is `&#123;count&#125;`
+pub fn escape_text(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('{', "&#123;")
+        .replace('}', "&#125;")
+}
+
+/// Variable of an image imported from its path, so the bundler resolves it, ex: `iconStarSrc`
+pub fn asset_import(path: &str, assets: &mut Vec<(String, String)>) -> String {
+    if let Some((asset, _)) = assets.iter().find(|(_, p)| p == path) {
+        return asset.clone();
+    }
+
+    let stem = path.rsplit('/').next().unwrap_or(path);
+    let stem = stem.split('.').next().unwrap_or(stem);
+    let asset = format!("{}Src", stem.to_case(Case::Camel));
+    assets.push((asset.clone(), path.to_string()));
+
+    asset
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn component(id: &str, name: &str, markup: Vec<Element>) -> Component {
+        Component {
+            id: id.to_string(),
+            name: name.to_string(),
+            css: String::new(),
+            markup,
+            is_set: false,
+            includes: vec![],
+        }
+    }
+
+    fn instance(id: &str, children: Vec<Element>) -> Element {
+        Element {
+            tag: "div".to_string(),
+            component: Some(id.to_string()),
+            children,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn imports() {
+        let mut components = BTreeMap::new();
+        for (id, name) in [("1:1", "button"), ("2:1", "icon"), ("3:1", "toolbar")] {
+            components.insert(id.to_string(), component(id, name, vec![]));
+        }
+        let library = Library {
+            components: &components,
+            tokens: &HashMap::new(),
+            variables: &Variables::default(),
+            units: &Units::default(),
+        };
+
+        // The icon is only nested in the toolbar instance
+        let card = Element {
+            tag: "div".to_string(),
+            children: vec![
+                instance("1:1", vec![]),
+                instance("3:1", vec![instance("2:1", vec![])]),
+                instance("1:1", vec![]),
+                instance("3:1", vec![]),
+            ],
+            ..Default::default()
+        };
+        let imports: Vec<&str> = library
+            .imports(&[&card])
+            .into_iter()
+            .map(|component| component.name.as_str())
+            .collect();
+
+        assert_eq!(imports, vec!["button", "toolbar"]);
+    }
+}
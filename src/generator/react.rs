@@ -0,0 +1,256 @@
+//! React component of each component: a `.tsx` function with typed props for the variant
+//! properties and the texts, styled by the generated css as a CSS module.
+
+use askama::Template;
+use convert_case::{Case, Casing};
+
+use super::{
+    asset_import, escape_attribute, json, prop_value, Component, Element, Generator, Library,
+};
+
+#[derive(Template)]
+#[template(path = "react.tsx", escape = "none")]
+struct ReactTemplate {
+    name: String,
+    file: String,
+    has_texts: bool,
+    /// Component name and file of the nested components
+    imports: Vec<(String, String)>,
+    /// Variable and path of the images
+    assets: Vec<(String, String)>,
+    /// Name and type of the props
+    props: Vec<(String, String)>,
+    params: String,
+    /// Condition and JSX of the variants, apart from the first one
+    variants: Vec<(String, String)>,
+    fallback: String,
+}
+
+pub struct React;
+
+impl Generator for React {
+    /// The css is a CSS module next to the component
+    fn generate(&self, component: &Component, library: &Library) -> Vec<(String, String)> {
+        vec![
+            ("tsx".to_string(), react_component(component, library)),
+            ("module.css".to_string(), component.css.clone()),
+        ]
+    }
+}
+
+/// `.tsx` module of the component, its css is imported from `{name}.module.css`
+fn react_component(component: &Component, library: &Library) -> String {
+    let elements = component.variants();
+    let mut assets: Vec<(String, String)> = Vec::new();
+
+    let values = component.variant_properties();
+
+    let mut texts: Vec<(String, String)> = Vec::new();
+    for element in elements.iter() {
+        for (slot, characters) in element.texts() {
+            let prop = slot.to_case(Case::Camel);
+            if !texts.iter().any(|(p, _)| p == &prop)
+                && !values.iter().any(|(a, _)| prop_name(a) == prop)
+            {
+                texts.push((prop, characters));
+            }
+        }
+    }
+
+    let mut props: Vec<(String, String)> = values
+        .iter()
+        .map(|(attribute, v)| {
+            (
+                prop_name(attribute),
+                v.iter().map(|v| json(v)).collect::<Vec<_>>().join(" | "),
+            )
+        })
+        .collect();
+    props.extend(
+        texts
+            .iter()
+            .map(|(prop, _)| (prop.clone(), "ReactNode".to_string())),
+    );
+
+    let defaults: Vec<String> = values
+        .iter()
+        .map(|(attribute, v)| format!("{} = {}", prop_name(attribute), json(&v[0])))
+        .chain(
+            texts
+                .iter()
+                .map(|(prop, characters)| format!("{prop} = {}", json(characters))),
+        )
+        .collect();
+    let params = if defaults.is_empty() {
+        "{}".to_string()
+    } else {
+        format!("{{ {} }}", defaults.join(", "))
+    };
+
+    let variants = elements
+        .iter()
+        .skip(1)
+        .filter(|element| !element.variant.is_empty())
+        .map(|element| {
+            let condition = element
+                .variant
+                .iter()
+                .map(|(attribute, value)| {
+                    format!("{} === {}", prop_name(attribute), json(&prop_value(value)))
+                })
+                .collect::<Vec<_>>()
+                .join(" && ");
+            (condition, jsx(element, library, &mut assets))
+        })
+        .collect();
+    let fallback = match elements.first() {
+        Some(element) => jsx(element, library, &mut assets),
+        None => "null".to_string(),
+    };
+
+    let imports = library
+        .imports(&elements)
+        .into_iter()
+        .map(|dt| (dt.name.to_case(Case::Pascal), dt.name.clone()))
+        .collect();
+
+    ReactTemplate {
+        name: component.name.to_case(Case::Pascal),
+        file: component.name.clone(),
+        has_texts: !texts.is_empty(),
+        imports,
+        assets,
+        props,
+        params,
+        variants,
+        fallback,
+    }
+    .render()
+    .unwrap()
+}
+
+/// JSX of the markup, the texts are their props and the instances are the nested components
+fn jsx(element: &Element, library: &Library, assets: &mut Vec<(String, String)>) -> String {
+    let attributes = &element.attributes;
+
+    if let Some(slot) = &element.slot {
+        return format!(
+            "<{tag}{attributes}>{{{prop}}}</{tag}>",
+            tag = element.tag,
+            attributes = jsx_attributes(attributes, &element.variant, assets),
+            prop = slot.to_case(Case::Camel)
+        );
+    }
+
+    if let Some(dt) = element.component.as_ref().and_then(|id| library.get(id)) {
+        let props: String = element
+            .variant
+            .iter()
+            .map(|(attribute, value)| {
+                format!(" {}={}", prop_name(attribute), json(&prop_value(value)))
+            })
+            .chain(element.texts().iter().map(|(slot, characters)| {
+                format!(" {}={{{}}}", slot.to_case(Case::Camel), json(characters))
+            }))
+            .collect();
+        return format!("<{}{props} />", dt.name.to_case(Case::Pascal));
+    }
+
+    let attributes = jsx_attributes(attributes, &element.variant, assets);
+    if element.children.is_empty() {
+        return format!("<{}{attributes} />", element.tag);
+    }
+
+    let children: Vec<String> = element
+        .children
+        .iter()
+        .map(|child| format!("  {}", jsx(child, library, assets).replace('\n', "\n  ")))
+        .collect();
+
+    format!(
+        "<{tag}{attributes}>\n{children}\n</{tag}>",
+        tag = element.tag,
+        children = children.join("\n")
+    )
+}
+
+/// Classes are keys of the CSS module and images are imported, so the bundler resolves them.
+/// The variant attributes aren't typed as HTML attributes, they are spread to skip the type check.
+fn jsx_attributes(
+    attributes: &[(String, String)],
+    variant: &[(String, String)],
+    assets: &mut Vec<(String, String)>,
+) -> String {
+    let spread: Vec<String> = attributes
+        .iter()
+        .filter(|(name, _)| variant.iter().any(|(attribute, _)| attribute == name))
+        .map(|(name, value)| format!("{}: {}", json(name), json(value)))
+        .collect();
+
+    let mut jsx: String = attributes
+        .iter()
+        .filter(|(name, _)| !variant.iter().any(|(attribute, _)| attribute == name))
+        .map(|(name, value)| match name.as_str() {
+            "class" => {
+                let classes: Vec<String> = value
+                    .split_whitespace()
+                    .map(|class| format!("styles[{}]", json(class)))
+                    .collect();
+                match classes.len() {
+                    1 => format!(" className={{{}}}", classes[0]),
+                    _ => format!(" className={{[{}].join(\" \")}}", classes.join(", ")),
+                }
+            }
+            "src" => format!(" src={{{}}}", asset_import(value, assets)),
+            _ => format!(" {name}=\"{}\"", escape_attribute(value)),
+        })
+        .collect();
+
+    if !spread.is_empty() {
+        jsx.push_str(&format!(" {{...{{ {} }}}}", spread.join(", ")));
+    }
+
+    jsx
+}
+
+fn prop_name(attribute: &str) -> String {
+    attribute.to_case(Case::Camel)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::LibraryFixture;
+
+    fn generate(name: &str) -> String {
+        let fixture = LibraryFixture::default();
+        React.generate(fixture.component(name), &fixture.library())[0]
+            .1
+            .clone()
+    }
+
+    #[test]
+    fn imports() {
+        let card = generate("card");
+
+        // The icon is only nested in the toolbar, the toolbar component imports it
+        assert!(card.contains(
+            "import Toolbar from \"../toolbar/toolbar\";\nimport Button from \"../button/button\";\nimport styles"
+        ));
+        assert!(card.contains("      <Toolbar />\n"));
+        assert!(card.contains(r#"<Button size="large" label={"Read more"} />"#));
+        assert!(generate("toolbar").contains(
+            "import Button from \"../button/button\";\nimport Icon from \"../icon/icon\";\n"
+        ));
+    }
+
+    #[test]
+    fn variants() {
+        let button = generate("button");
+
+        assert!(button.contains(r#"size?: "small" | "large" | "x'l\"";"#));
+        assert!(button.contains(r#"({ size = "small", label = "Button" }: ButtonProps)"#));
+        assert!(button.contains(r#"if (size === "x'l\"") {"#));
+        assert!(button.contains(r#"<div className={styles["button"]} {...{ "size": "x'l\"" }}>"#));
+    }
+}
@@ -0,0 +1,273 @@
+//! Svelte component of each component: the variant properties are props bound to the variant
+//! attributes of the markup, so the attribute selectors of the generated css apply, and the texts are slots.
+
+use askama::Template;
+use convert_case::{Case, Casing};
+
+use super::{asset_import, escape_text, json, prop_value, Component, Element, Generator, Library};
+
+#[derive(Template)]
+#[template(path = "svelte.svelte", escape = "none")]
+struct SvelteTemplate {
+    /// Component name and file of the nested components
+    imports: Vec<(String, String)>,
+    /// Variable and path of the images
+    assets: Vec<(String, String)>,
+    /// Name, type and default value of the props
+    props: Vec<(String, String, String)>,
+    markup: String,
+    styles: String,
+}
+
+pub struct Svelte;
+
+impl Generator for Svelte {
+    fn generate(&self, component: &Component, library: &Library) -> Vec<(String, String)> {
+        vec![("svelte".to_string(), svelte_component(component, library))]
+    }
+}
+
+/// `.svelte` component, the variants only need `{#if}` blocks when their markup differs
+fn svelte_component(component: &Component, library: &Library) -> String {
+    let props = component
+        .variant_properties()
+        .into_iter()
+        .map(|(attribute, values)| {
+            let prop_type: Vec<String> = values.iter().map(|v| json(v)).collect();
+            (
+                attribute.to_case(Case::Camel),
+                prop_type.join(" | "),
+                json(&values[0]),
+            )
+        })
+        .collect();
+
+    // Markup of each variant with its attributes bound to the props, and the condition to render it
+    let mut variants: Vec<(String, String)> = Vec::new();
+    let mut assets: Vec<(String, String)> = Vec::new();
+    for element in component.variants() {
+        let condition: Vec<String> = element
+            .variant
+            .iter()
+            .map(|(attribute, value)| {
+                format!(
+                    "{} === {}",
+                    attribute.to_case(Case::Camel),
+                    json(&prop_value(value))
+                )
+            })
+            .collect();
+
+        let mut markup = element
+            .escaped(&escape_text)
+            .slotted(library, &imported_component);
+        bind_attributes(&mut markup.attributes, &element.variant);
+        import_assets(&mut markup, &mut assets);
+        let markup = markup.render().unwrap();
+
+        if !variants.iter().any(|(_, m)| m == &markup) {
+            variants.push((condition.join(" && "), markup));
+        }
+    }
+
+    // The first variant is the fallback of the others
+    let markup = match variants.len() {
+        0 => String::new(),
+        1 => variants[0].1.clone(),
+        _ => {
+            let mut blocks: Vec<String> = Vec::new();
+            for (index, (condition, markup)) in variants.iter().enumerate().skip(1) {
+                let block = match index {
+                    1 => "#if",
+                    _ => ":else if",
+                };
+                blocks.push(format!("{{{block} {condition}}}\n  {}", indent(markup)));
+            }
+            blocks.push(format!("{{:else}}\n  {}", indent(&variants[0].1)));
+            blocks.push("{/if}".to_string());
+            blocks.join("\n")
+        }
+    };
+
+    let imports = library
+        .imports(&component.variants())
+        .into_iter()
+        .map(|dt| (dt.name.to_case(Case::Pascal), dt.name.clone()))
+        .collect();
+
+    SvelteTemplate {
+        imports,
+        assets,
+        props,
+        markup,
+        styles: component.css.clone(),
+    }
+    .render()
+    .unwrap()
+}
+
+/// The variant attributes are bound to their props, ex: ` size="large"` is ` size="{size}"`
+fn bind_attributes(attributes: &mut [(String, String)], variant: &[(String, String)]) {
+    for (name, value) in attributes.iter_mut() {
+        if variant.iter().any(|(attribute, _)| attribute == name) {
+            *value = format!("{{{}}}", name.to_case(Case::Camel));
+        }
+    }
+}
+
+/// Images are imported, so the bundler resolves them
+fn import_assets(element: &mut Element, assets: &mut Vec<(String, String)>) {
+    if element.tag == "img" {
+        for (name, value) in element.attributes.iter_mut() {
+            if name == "src" {
+                *value = format!("{{{}}}", asset_import(value, assets));
+            }
+        }
+    }
+
+    for child in element.children.iter_mut() {
+        import_assets(child, assets);
+    }
+}
+
+/// Instances of the generated components are the imported components, with the texts of the instance in their slots
+fn imported_component(component: &Component, instance: &Element) -> Element {
+    Element {
+        tag: component.name.to_case(Case::Pascal),
+        attributes: instance
+            .variant
+            .iter()
+            .map(|(name, value)| (name.clone(), prop_value(value)))
+            .collect(),
+        children: instance
+            .texts()
+            .into_iter()
+            .map(|(slot, characters)| Element {
+                tag: "svelte:fragment".to_string(),
+                attributes: vec![("slot".to_string(), slot)],
+                characters,
+                ..Default::default()
+            })
+            .collect(),
+        ..Default::default()
+    }
+}
+
+fn indent(markup: &str) -> String {
+    markup.replace('\n', "\n  ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::Units;
+    use crate::test::LibraryFixture;
+    use crate::types::variables::Variables;
+    use std::collections::{BTreeMap, HashMap};
+
+    fn variant(size: &str, children: Vec<Element>) -> Element {
+        Element {
+            tag: "div".to_string(),
+            attributes: vec![
+                ("class".to_string(), "button".to_string()),
+                ("size".to_string(), size.to_string()),
+            ],
+            variant: vec![("size".to_string(), size.to_string())],
+            children,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn variants_markup() {
+        let icon = Element {
+            tag: "img".to_string(),
+            attributes: vec![
+                ("class".to_string(), "icon".to_string()),
+                ("src".to_string(), "../../assets/icon.svg".to_string()),
+                ("alt".to_string(), "Icon".to_string()),
+            ],
+            ..Default::default()
+        };
+        let component = Component {
+            id: "1:1".to_string(),
+            name: "button".to_string(),
+            css: String::new(),
+            markup: vec![Element {
+                children: vec![variant("small", vec![]), variant("large", vec![icon])],
+                ..Default::default()
+            }],
+            is_set: true,
+            includes: vec![],
+        };
+
+        assert_eq!(
+            Svelte.generate(
+                &component,
+                &Library {
+                    components: &BTreeMap::new(),
+                    tokens: &HashMap::new(),
+                    variables: &Variables::default(),
+                    units: &Units::default(),
+                }
+            )[0]
+            .1,
+            r#"<script lang="ts">
+  import iconSrc from "../../assets/icon.svg";
+
+  export let size: "small" | "large" = "small";
+</script>
+
+{#if size === "large"}
+  <div class="button" size="{size}">
+      <img class="icon" src="{iconSrc}" alt="Icon">
+  </div>
+{:else}
+  <div class="button" size="{size}">
+  </div>
+{/if}"#
+        );
+    }
+
+    #[test]
+    fn imports() {
+        let fixture = LibraryFixture::default();
+        let generate = |name| {
+            Svelte.generate(fixture.component(name), &fixture.library())[0]
+                .1
+                .clone()
+        };
+        let card = generate("card");
+
+        // The icon is only nested in the toolbar, the toolbar component imports it
+        assert!(card.starts_with(
+            "<script lang=\"ts\">\n  import Toolbar from \"../toolbar/toolbar.svelte\";\n  import Button from \"../button/button.svelte\";\n</script>"
+        ));
+        assert!(card.contains(
+            "<Button size=\"large\">\n        <svelte:fragment slot=\"label\">Read more"
+        ));
+        assert_eq!(generate("toolbar").matches("import Button").count(), 1);
+        // The variants only differ by their attributes, bound to the props
+        let button = generate("button");
+        assert!(button.contains(r#"export let size: "small" | "large" | "x'l\"" = "small";"#));
+        assert!(!button.contains("{#if"));
+    }
+
+    #[test]
+    fn escaped_texts() {
+        let fixture = LibraryFixture::default();
+        let card = Svelte.generate(fixture.component("card"), &fixture.library())[0]
+            .1
+            .clone();
+        let toolbar = Svelte.generate(fixture.component("toolbar"), &fixture.library())[0]
+            .1
+            .clone();
+
+        assert!(card.contains(
+            "<slot name=\"title\">&#123;&#123; count &#125;&#125; of &#123;total&#125; &lt;items&gt;</slot>"
+        ));
+        assert!(
+            toolbar.contains("<svelte:fragment slot=\"label\">Next &#123;page&#125; &amp; more\n")
+        );
+    }
+}
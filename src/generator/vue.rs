@@ -0,0 +1,181 @@
+//! Vue single-file component of each component: the markup in the `<template>` with a `<slot>`
+//! for each text, the variant properties as props and the generated css as a scoped style.
+
+use askama::Template;
+use convert_case::{Case, Casing};
+
+use super::{escape_text, json, prop_value, Component, Element, Generator, Library};
+
+#[derive(Template)]
+#[template(path = "vue.vue", escape = "none")]
+struct VueTemplate {
+    /// Component name and file of the nested components
+    imports: Vec<(String, String)>,
+    /// Name, type and default value of the props
+    props: Vec<(String, String, String)>,
+    markup: String,
+    styles: String,
+}
+
+pub struct Vue;
+
+impl Generator for Vue {
+    fn generate(&self, component: &Component, library: &Library) -> Vec<(String, String)> {
+        vec![("vue".to_string(), vue_component(component, library))]
+    }
+}
+
+/// `.vue` SFC of the component, the variants are rendered with `v-if` on the props
+fn vue_component(component: &Component, library: &Library) -> String {
+    let props = component
+        .variant_properties()
+        .into_iter()
+        .map(|(attribute, values)| {
+            let prop_type: Vec<String> = values.iter().map(|v| json(v)).collect();
+            (
+                attribute.to_case(Case::Camel),
+                prop_type.join(" | "),
+                json(&values[0]),
+            )
+        })
+        .collect();
+
+    let elements: Vec<&Element> = component
+        .variants()
+        .into_iter()
+        .enumerate()
+        .filter(|(index, element)| *index == 0 || !element.variant.is_empty())
+        .map(|(_, element)| element)
+        .collect();
+    let mut markup: Vec<String> = Vec::new();
+
+    // The first variant is the fallback of the others
+    for (index, element) in elements
+        .iter()
+        .enumerate()
+        .skip(1)
+        .chain(elements.iter().enumerate().take(1))
+    {
+        let condition: Vec<String> = element
+            .variant
+            .iter()
+            .map(|(attribute, value)| {
+                format!(
+                    "{} === {}",
+                    attribute.to_case(Case::Camel),
+                    quoted_string(&prop_value(value))
+                )
+            })
+            .collect();
+
+        let directive = match (elements.len(), index, markup.len()) {
+            (1, _, _) => None,
+            (_, 0, _) => Some(("v-else", String::new())),
+            (_, _, 0) => Some(("v-if", condition.join(" && "))),
+            _ => Some(("v-else-if", condition.join(" && "))),
+        };
+
+        let mut element = element
+            .escaped(&escape_text)
+            .slotted(library, &imported_component);
+        if let Some((name, value)) = directive {
+            element.attributes.insert(0, (name.to_string(), value));
+        }
+        markup.push(element.render().unwrap());
+    }
+
+    let imports = library
+        .imports(&elements)
+        .into_iter()
+        .map(|dt| (dt.name.to_case(Case::Pascal), dt.name.clone()))
+        .collect();
+
+    VueTemplate {
+        imports,
+        props,
+        markup: markup.join("\n"),
+        styles: component.css.clone(),
+    }
+    .render()
+    .unwrap()
+}
+
+/// Single-quoted JS string literal of the value, so the double-quoted directives only need their
+/// `"` escaped as HTML, ex: `'large'`
+fn quoted_string(value: &str) -> String {
+    let json = json(value);
+    let content = json[1..json.len() - 1]
+        .replace("\\\"", "\"")
+        .replace('\'', "\\'");
+
+    format!("'{content}'")
+}
+
+/// Instances of the generated components are the imported components, with the texts of the instance in their slots
+fn imported_component(component: &Component, instance: &Element) -> Element {
+    Element {
+        tag: component.name.to_case(Case::Pascal),
+        attributes: instance
+            .variant
+            .iter()
+            .map(|(name, value)| (name.clone(), prop_value(value)))
+            .collect(),
+        children: instance
+            .texts()
+            .into_iter()
+            .map(|(slot, characters)| Element {
+                tag: "template".to_string(),
+                attributes: vec![(format!("#{slot}"), String::new())],
+                characters,
+                ..Default::default()
+            })
+            .collect(),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::LibraryFixture;
+
+    fn generate(name: &str) -> String {
+        let fixture = LibraryFixture::default();
+        Vue.generate(fixture.component(name), &fixture.library())[0]
+            .1
+            .clone()
+    }
+
+    #[test]
+    fn imports() {
+        let card = generate("card");
+
+        // The icon is only nested in the toolbar, the toolbar component imports it
+        assert!(card.starts_with(
+            "<script setup lang=\"ts\">\nimport Toolbar from \"../toolbar/toolbar.vue\";\nimport Button from \"../button/button.vue\";\n</script>"
+        ));
+        assert!(card.contains("<Button size=\"large\">\n          <template #label>Read more"));
+        assert_eq!(generate("toolbar").matches("import Button").count(), 1);
+    }
+
+    #[test]
+    fn variants() {
+        let button = generate("button");
+
+        assert!(button.contains(r#"size?: "small" | "large" | "x'l\"";"#));
+        assert!(button.contains(r#"<div v-if="size === 'large'" class="button" size="large">"#));
+        assert!(button.contains(
+            r#"<div v-else-if="size === 'x\'l&quot;'" class="button" size="x'l&quot;">"#
+        ));
+        assert!(button.contains(r#"<div v-else class="button" size="small">"#));
+    }
+
+    #[test]
+    fn escaped_texts() {
+        // The braces aren't interpolated and `<` doesn't open a tag
+        assert!(generate("card").contains(
+            "<slot name=\"title\">&#123;&#123; count &#125;&#125; of &#123;total&#125; &lt;items&gt;</slot>"
+        ));
+        assert!(generate("toolbar").contains("<template #label>Next &#123;page&#125; &amp; more\n"));
+    }
+}
@@ -0,0 +1,156 @@
+//! Custom element of each component: the generated css in its Shadow DOM, a variant for each
+//! combination of the variant attributes and a `<slot>` for each text.
+
+use askama::Template;
+use convert_case::{Case, Casing};
+
+use super::{Component, Element, Generator, Library};
+
+#[derive(Template)]
+#[template(path = "web_component.js", escape = "none")]
+struct WebComponentTemplate {
+    class_name: String,
+    tag: String,
+    /// Names of the components of the nested custom elements
+    imports: Vec<String>,
+    styles: String,
+    /// JSON array of the attribute names
+    observed: String,
+    /// JSON object of the attributes, and the markup of each variant
+    variants: Vec<(String, String)>,
+}
+
+pub struct WebComponents {
+    /// Prefix of the element names, ex: `fg` for `<fg-button>`
+    pub tag_prefix: String,
+}
+
+impl Generator for WebComponents {
+    fn generate(&self, component: &Component, library: &Library) -> Vec<(String, String)> {
+        let module = web_component(component, library, &self.tag_prefix);
+
+        vec![("js".to_string(), module)]
+    }
+}
+
+/// `.js` module defining the `<{prefix}-{name}>` element of the component
+fn web_component(component: &Component, library: &Library, prefix: &str) -> String {
+    let tag = format!("{prefix}-{}", component.name);
+    let mut observed: Vec<&String> = Vec::new();
+    let mut variants: Vec<(String, String)> = Vec::new();
+
+    for element in component.variants() {
+        for (attribute, _) in element.variant.iter() {
+            if !observed.contains(&attribute) {
+                observed.push(attribute);
+            }
+        }
+
+        let attributes: serde_json::Map<String, serde_json::Value> = element
+            .variant
+            .iter()
+            .map(|(name, value)| (name.clone(), value.clone().into()))
+            .collect();
+        let markup = element
+            .slotted(library, &|dt, instance| {
+                custom_element(dt, instance, prefix)
+            })
+            .render()
+            .unwrap();
+
+        variants.push((
+            serde_json::Value::Object(attributes).to_string(),
+            template_literal(&markup),
+        ));
+    }
+
+    let imports = library
+        .imports(&component.variants())
+        .into_iter()
+        .map(|dt| dt.name.clone())
+        .collect();
+
+    WebComponentTemplate {
+        class_name: tag.to_case(Case::Pascal),
+        tag,
+        imports,
+        styles: template_literal(&component.css),
+        observed: serde_json::to_string(&observed).unwrap(),
+        variants,
+    }
+    .render()
+    .unwrap()
+}
+
+/// Instances of the generated components are their custom elements, with the texts of the instance slotted in
+fn custom_element(component: &Component, instance: &Element, prefix: &str) -> Element {
+    Element {
+        tag: format!("{prefix}-{}", component.name),
+        attributes: instance.variant.clone(),
+        children: instance
+            .texts()
+            .into_iter()
+            .map(|(slot, characters)| Element {
+                tag: "span".to_string(),
+                attributes: vec![("slot".to_string(), slot)],
+                characters,
+                ..Default::default()
+            })
+            .collect(),
+        ..Default::default()
+    }
+}
+
+/// Escapes the content of a JS template literal
+fn template_literal(content: &str) -> String {
+    content
+        .replace('\\', "\\\\")
+        .replace('`', "\\`")
+        .replace("${", "\\${")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::LibraryFixture;
+
+    fn generate(name: &str) -> String {
+        let fixture = LibraryFixture::default();
+        let generator = WebComponents {
+            tag_prefix: "ds".to_string(),
+        };
+        generator.generate(fixture.component(name), &fixture.library())[0]
+            .1
+            .clone()
+    }
+
+    #[test]
+    fn imports() {
+        let card = generate("card");
+
+        // The icon is only nested in the toolbar, the toolbar module imports it
+        assert!(card
+            .starts_with("import \"../toolbar/toolbar.js\";\nimport \"../button/button.js\";\n\n"));
+        assert!(card.contains("<ds-toolbar>\n        </ds-toolbar>"));
+        assert!(
+            card.contains("<ds-button size=\"large\">\n            <span slot=\"label\">Read more")
+        );
+        assert_eq!(
+            generate("toolbar")
+                .matches("import \"../button/button.js\";")
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn variants() {
+        let button = generate("button");
+
+        assert!(button.contains(r#"return ["size"];"#));
+        assert!(button.contains(r#"attributes: {"size":"small"},"#));
+        assert!(button.contains(r#"attributes: {"size":"x'l\""},"#));
+        assert!(button.contains(r#"<div class="button" size="x'l&quot;">"#));
+        assert!(button.contains(r#"customElements.define("ds-button", DsButton);"#));
+    }
+}
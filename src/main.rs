@@ -1,57 +1,373 @@
 use crate::prelude::*;
+use api::HttpClient;
 use clap::Parser;
-use types::file::FigmaData;
-use utils::parse_components::parse;
+use cli::Command;
+use types::{
+    file::FigmaData,
+    variables::{LocalVariables, Variables},
+};
+use utils::{
+    assets::{collect_assets, collect_image_refs, ASSETS_DIR},
+    inspect::inspect,
+    parse_components::{parse, parse_tokens},
+};
 
+mod api;
+mod cache;
 mod cli;
+mod config;
 mod error;
+mod generator;
 mod prelude;
 
 mod types;
 mod utils;
 
-async fn load(figma_config: &cli::FigmaConfig) -> Result<FigmaData> {
-    let document = reqwest::Client::new()
-        .get(&format!(
-            "https://api.figma.com/v1/files/{}",
-            figma_config.file.clone()
-        ))
-        .header("X-Figma-Token", figma_config.token.clone())
-        .send()
-        .await?
-        .bytes()
-        .await?;
-
-    std::fs::create_dir_all("figma_output/")?;
-    std::fs::write("figma_output/original_output.json", &document)?;
-
-    let data: FigmaData = serde_json::from_slice(&document)?;
-    std::fs::write(
-        "figma_output/cache.json",
-        serde_json::to_string_pretty(&data).unwrap(),
-    )?;
+#[cfg(test)]
+mod test;
+
+async fn load(
+    client: &impl HttpClient,
+    figma_config: &cli::FigmaConfig,
+    settings: &cli::Settings,
+) -> Result<FigmaData> {
+    let cache = settings.output.cache();
+    let file = &figma_config.file;
+
+    if !settings.refresh && !figma_config.partial && cache.read_meta(file).is_some() {
+        let meta = api::get_file_meta(client, file).await?;
+        if cache.is_fresh(file, &meta.version, &meta.last_modified) {
+            return cache.read(file);
+        }
+    }
+
+    let data: FigmaData = if figma_config.partial {
+        api::get_partial_file(client, file, &settings.filter).await?
+    } else {
+        let document = client.get(&api::file_path(file, None)).await?;
+
+        // The token should never be part of the response, but make sure it's not saved to disk
+        cache.write_original_output(
+            file,
+            &figma_config
+                .token
+                .redact(&String::from_utf8_lossy(&document)),
+        )?;
+
+        serde_json::from_slice(&document)?
+    };
+
+    cache.write(file, &data, figma_config.partial)?;
+
     Ok(data)
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    let cmd = cli::Cli::parse();
+    let settings = cli::Cli::parse().settings()?;
+
+    let (file, file_key) = if let Some(figma_config) = &settings.figma_config {
+        let client = api::FigmaClient::new(figma_config)?;
+        let cached = settings.output.cache().read_meta(&figma_config.file);
+        let file = load(&client, figma_config, &settings).await?;
+
+        fetch_variables(&client, figma_config, &settings).await?;
+
+        // Assets on disk are still up to date when the file didn't change
+        let unchanged = !settings.refresh
+            && cached.is_some_and(|meta| {
+                meta.version == file.version && meta.last_modified == file.last_modified
+            });
+        export_assets(&client, figma_config, &settings, &file, unchanged).await?;
 
-    let file = if let Some(figma_config) = cmd.figma_config {
-        load(&figma_config).await?
+        (file, figma_config.file.clone())
     } else {
-        let full_doc = std::fs::read("figma_output/cache.json")?;
-        serde_json::from_slice(&full_doc)?
+        let file_key = cache_key(&settings)?;
+        (settings.output.cache().read(&file_key)?, file_key)
+    };
+    let variables = read_variables(&settings, &file_key)?;
+
+    match settings.command {
+        Some(Command::Fetch { .. }) => Ok(()),
+        Some(Command::Tokens) => {
+            parse_tokens(&file, &settings.output, &variables);
+            Ok(())
+        }
+        Some(Command::Inspect { depth }) => {
+            println!("{}", inspect(&file.document, depth));
+            Ok(())
+        }
+        Some(Command::Build) | None => build(file, &settings, &variables),
+    }
+}
+
+async fn export_assets(
+    client: &impl HttpClient,
+    figma_config: &cli::FigmaConfig,
+    settings: &cli::Settings,
+    file: &FigmaData,
+    skip_existing: bool,
+) -> Result<()> {
+    let assets_dir = settings.output.out_dir.join(ASSETS_DIR);
+
+    api::download_assets(
+        client,
+        &figma_config.file,
+        &collect_assets(&file.document),
+        &assets_dir,
+        skip_existing,
+    )
+    .await?;
+
+    api::download_image_fills(
+        client,
+        &figma_config.file,
+        &collect_image_refs(&file.document),
+        &assets_dir,
+        skip_existing,
+    )
+    .await
+}
+
+/// Saves the local variables into the cache, when they are requested
+async fn fetch_variables(
+    client: &impl HttpClient,
+    figma_config: &cli::FigmaConfig,
+    settings: &cli::Settings,
+) -> Result<()> {
+    if !figma_config.variables {
+        return Ok(());
+    }
+
+    let variables = api::get_local_variables(client, &figma_config.file).await?;
+    settings
+        .output
+        .cache()
+        .write_variables(&figma_config.file, &variables)
+}
+
+/// File key of the cache to use when offline
+fn cache_key(settings: &cli::Settings) -> Result<String> {
+    match &settings.cache_key {
+        Some(key) => Ok(key.clone()),
+        None => settings.output.cache().latest().ok_or(Error::MissingFile),
+    }
+}
+
+/// Variables from the `--variables` file, or the ones fetched with the file
+fn read_variables(settings: &cli::Settings, file_key: &str) -> Result<Variables> {
+    let Some(path) = &settings.variables_file else {
+        return Ok(settings
+            .output
+            .cache()
+            .read_variables(file_key)
+            .unwrap_or_default());
     };
 
+    let content = std::fs::read(path).map_err(|e| Error::Variables {
+        path: path.clone(),
+        message: e.to_string(),
+    })?;
+    let variables: LocalVariables =
+        serde_json::from_slice(&content).map_err(|e| Error::Variables {
+            path: path.clone(),
+            message: e.to_string(),
+        })?;
+
+    Ok(variables.meta)
+}
+
+fn build(file: FigmaData, settings: &cli::Settings, variables: &Variables) -> Result<()> {
+    let output = &settings.output;
+
     if file.components.is_empty() {
         return Err(error::Error::NoComponent);
     }
 
     // Make sure output folder exists
-    std::fs::create_dir_all("figma_output/components")?;
+    std::fs::create_dir_all(output.out_dir.join("components"))?;
 
-    parse(file);
+    let generator = output.target.generator(&output.tag_prefix);
+    parse(
+        file,
+        output,
+        &settings.filter,
+        variables,
+        generator.as_ref(),
+    );
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::{FixtureClient, FILE_FIXTURE, VARIABLES_FIXTURE};
+
+    fn settings(args: &[&str]) -> cli::Settings {
+        cli::Cli::parse_from([&["fg-export", "-t", "secret"], args].concat())
+            .settings()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn pipeline_from_fixture() {
+        let dir = tempfile::tempdir().unwrap();
+        let out = dir.path().to_str().unwrap();
+        let settings = settings(&["-o", out, "--fetch-variables", "abc"]);
+        let figma_config = settings.figma_config.as_ref().unwrap();
+
+        let client = FixtureClient::default()
+            .with("/files/abc?geometry=paths", FILE_FIXTURE)
+            .with(
+                "/images/abc?ids=2:5&format=svg&scale=1",
+                r#"{ "err": null, "images": { "2:5": "https://images.test/star.svg" } }"#,
+            )
+            .with("https://images.test/star.svg", "<svg></svg>")
+            .with(
+                "/files/abc/images",
+                r#"{ "error": false, "status": 200, "meta": { "images": { "cover-image": "https://images.test/cover" } } }"#,
+            )
+            .with("https://images.test/cover", "GIF89a")
+            .with("/files/abc/variables/local", VARIABLES_FIXTURE);
+        let file = load(&client, figma_config, &settings).await.unwrap();
+        export_assets(&client, figma_config, &settings, &file, false)
+            .await
+            .unwrap();
+        fetch_variables(&client, figma_config, &settings)
+            .await
+            .unwrap();
+        build(file, &settings, &read_variables(&settings, "abc").unwrap()).unwrap();
+
+        let components = dir.path().join("components");
+        assert!(components.join("button/button.html").is_file());
+        assert!(components.join("input/input.html").is_file());
+        let theme = std::fs::read_to_string(dir.path().join("css/theme.css")).unwrap();
+        assert!(theme.contains("--label-medium-font-size: 14px;"));
+        assert!(dir.path().join("tokens.json").is_file());
+        assert!(theme.contains(".text-label-medium {"));
+        assert!(theme.contains("font-weight: var(--label-medium-font-weight);"));
+        let button = std::fs::read_to_string(components.join("button/button.html")).unwrap();
+        assert!(button.contains("var(--label-medium-font-family)"));
+        // Variables modes are themes, and the bound fill uses the variable
+        assert!(theme.contains("--button-background: var(--brand-blue);"));
+        assert!(theme.contains(".theme-dark {"));
+        assert!(button.contains("background: var(--button-background)"));
+        // Instances of other components bring their styles along
+        let card = std::fs::read_to_string(components.join("card/card.html")).unwrap();
+        assert!(card.contains(".button"));
+        // The rotated badge keeps its untransformed size, and is rotated around its center
+        assert!(card.contains("transform: rotate(-90deg);"));
+        assert!(card.contains("transform-origin: center;"));
+        assert!(card.contains("width: 40px;\n          height: 20px;"));
+        assert!(card.contains(
+            r#"<img class="icon-star" src="../../assets/icon-star.svg" alt="Icon/Star" width="24" height="24">"#
+        ));
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("assets/icon-star.svg")).unwrap(),
+            "<svg></svg>"
+        );
+        assert!(
+            card.contains(r#"url("../../assets/images/cover-image.gif") center / cover no-repeat"#)
+        );
+        assert!(dir.path().join("assets/images/cover-image.gif").is_file());
+
+        // The cached file is up to date, only its version is requested
+        let client = FixtureClient::default().with("/files/abc?depth=1", FILE_FIXTURE);
+        let file = load(&client, figma_config, &settings).await.unwrap();
+        assert_eq!(file.version, "100");
+        assert_eq!(*client.requests.borrow(), vec!["/files/abc?depth=1"]);
+    }
+
+    #[tokio::test]
+    async fn component_targets() {
+        // Files of the card, and its import of the nested button
+        let targets = [
+            (
+                "web-components",
+                vec!["card.js"],
+                r#"import "../button/button.js";"#,
+            ),
+            (
+                "react",
+                vec!["card.tsx", "card.module.css"],
+                r#"import Button from "../button/button";"#,
+            ),
+            (
+                "vue",
+                vec!["card.vue"],
+                r#"import Button from "../button/button.vue";"#,
+            ),
+            (
+                "svelte",
+                vec!["card.svelte"],
+                r#"import Button from "../button/button.svelte";"#,
+            ),
+        ];
+
+        for (target, files, import) in targets {
+            let dir = tempfile::tempdir().unwrap();
+            let out = dir.path().to_str().unwrap();
+            let settings = settings(&["-o", out, "--target", target, "--component", "Card", "abc"]);
+            let figma_config = settings.figma_config.as_ref().unwrap();
+
+            let client = FixtureClient::default().with("/files/abc?geometry=paths", FILE_FIXTURE);
+            let file = load(&client, figma_config, &settings).await.unwrap();
+            build(file, &settings, &Variables::default()).unwrap();
+
+            let components = dir.path().join("components");
+            for file in files.iter() {
+                assert!(
+                    components.join("card").join(file).is_file(),
+                    "{target}: {file}"
+                );
+            }
+            assert!(!components.join("card/card.html").exists(), "{target}");
+            let card = std::fs::read_to_string(components.join("card").join(files[0])).unwrap();
+            assert!(card.contains(import), "{target}");
+            assert!(card.contains("Card title"), "{target}");
+            assert!(card.contains("Read more"), "{target}");
+            // The nested components are generated even when they are not selected, the others aren't
+            assert!(components.join("button").is_dir(), "{target}");
+            assert!(!components.join("input").exists(), "{target}");
+        }
+    }
+
+    #[tokio::test]
+    async fn pipeline_from_server() {
+        let mut server = mockito::Server::new_async().await;
+        let file_mock = server
+            .mock("GET", "/files/abc")
+            .match_query(mockito::Matcher::UrlEncoded(
+                "geometry".to_string(),
+                "paths".to_string(),
+            ))
+            .match_header("X-Figma-Token", "secret")
+            .with_body(FILE_FIXTURE)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let out = dir.path().to_str().unwrap();
+        let settings = settings(&[
+            "--api-url",
+            &server.url(),
+            "-o",
+            out,
+            "--page",
+            "Forms",
+            "abc",
+        ]);
+        let figma_config = settings.figma_config.as_ref().unwrap();
+
+        let client = api::FigmaClient::new(figma_config).unwrap();
+        let file = load(&client, figma_config, &settings).await.unwrap();
+        build(file, &settings, &Variables::default()).unwrap();
+
+        file_mock.assert_async().await;
+        let components = dir.path().join("components");
+        assert!(components.join("input/input.html").is_file());
+        assert!(!components.join("button").exists());
+    }
+}
@@ -1 +1,187 @@
+//! Helpers to run the pipeline offline against recorded Figma responses, and a library of components for the generators
 
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashMap},
+};
+
+use crate::api::HttpClient;
+use crate::cli::Units;
+use crate::generator::{Component, Element, Library};
+use crate::prelude::*;
+use crate::types::{token::Token, variables::Variables};
+
+pub const FILE_FIXTURE: &str = include_str!("../tests/fixtures/file.json");
+pub const VARIABLES_FIXTURE: &str = include_str!("../tests/fixtures/variables.json");
+
+/// Serves recorded responses by request path (or url for downloads), and keeps track of the requested paths.
+#[derive(Default)]
+pub struct FixtureClient {
+    responses: HashMap<String, String>,
+    pub requests: RefCell<Vec<String>>,
+}
+
+impl FixtureClient {
+    pub fn with(mut self, path: &str, body: &str) -> FixtureClient {
+        self.responses.insert(path.to_string(), body.to_string());
+        self
+    }
+}
+
+impl HttpClient for FixtureClient {
+    async fn get(&self, path: &str) -> Result<Vec<u8>> {
+        self.requests.borrow_mut().push(path.to_string());
+
+        match self.responses.get(path) {
+            Some(body) => Ok(body.as_bytes().to_vec()),
+            None => Err(Error::NotFound(path.to_string())),
+        }
+    }
+
+    async fn download(&self, url: &str) -> Result<Vec<u8>> {
+        self.get(url).await
+    }
+}
+
+/// Components of a design system for the generators: a `button` set, an `icon`, a `toolbar` with
+/// two buttons and an icon, and a `card` using a toolbar and a button.
+/// The `x'l"` size checks the escaping of the variant values, the title and the text of the
+/// second toolbar button the escaping of the texts.
+pub struct LibraryFixture {
+    components: BTreeMap<String, Component>,
+    tokens: HashMap<String, Token>,
+    variables: Variables,
+    units: Units,
+}
+
+impl LibraryFixture {
+    pub fn library(&self) -> Library<'_> {
+        Library {
+            components: &self.components,
+            tokens: &self.tokens,
+            variables: &self.variables,
+            units: &self.units,
+        }
+    }
+
+    pub fn component(&self, name: &str) -> &Component {
+        self.components.values().find(|c| c.name == name).unwrap()
+    }
+}
+
+impl Default for LibraryFixture {
+    fn default() -> Self {
+        let label = |characters: &str| Element {
+            slot: Some("label".to_string()),
+            characters: characters.to_string(),
+            ..element("span", &[("class", "label")], vec![])
+        };
+        let button = |size: &str, characters: &str| Element {
+            variant: vec![("size".to_string(), size.to_string())],
+            ..element(
+                "div",
+                &[("class", "button"), ("size", size)],
+                vec![label(characters)],
+            )
+        };
+        let icon = || {
+            element(
+                "div",
+                &[("class", "icon")],
+                vec![element(
+                    "img",
+                    &[
+                        ("class", "star"),
+                        ("src", "../../assets/star.svg"),
+                        ("alt", "Star"),
+                    ],
+                    vec![],
+                )],
+            )
+        };
+        let toolbar = || {
+            element(
+                "div",
+                &[("class", "toolbar")],
+                vec![
+                    instance("1:1", button("small", "Button")),
+                    instance("1:1", button("large", "Next {page} & more")),
+                    instance("2:1", icon()),
+                ],
+            )
+        };
+        let card = element(
+            "div",
+            &[("class", "card")],
+            vec![
+                Element {
+                    slot: Some("title".to_string()),
+                    characters: "{{ count }} of {total} <items>".to_string(),
+                    ..element("span", &[("class", "title")], vec![])
+                },
+                instance("3:1", toolbar()),
+                instance("1:1", button("large", "Read more")),
+            ],
+        );
+
+        let components = [
+            (
+                "1:1",
+                "button",
+                element(
+                    "div",
+                    &[("class", "button")],
+                    vec![
+                        button("small", "Button"),
+                        button("large", "Button"),
+                        button("x'l\"", "Button"),
+                    ],
+                ),
+                &[][..],
+            ),
+            ("2:1", "icon", icon(), &[][..]),
+            ("3:1", "toolbar", toolbar(), &["1:1", "2:1"][..]),
+            ("4:1", "card", card, &["3:1", "1:1", "2:1"][..]),
+        ];
+
+        LibraryFixture {
+            components: components
+                .into_iter()
+                .map(|(id, name, markup, includes)| {
+                    let component = Component {
+                        id: id.to_string(),
+                        name: name.to_string(),
+                        css: format!(".{name}{{display:flex}}"),
+                        markup: vec![markup],
+                        is_set: name == "button",
+                        includes: includes.iter().map(|id| id.to_string()).collect(),
+                    };
+                    (id.to_string(), component)
+                })
+                .collect(),
+            tokens: HashMap::new(),
+            variables: Variables::default(),
+            units: Units::default(),
+        }
+    }
+}
+
+fn element(tag: &str, attributes: &[(&str, &str)], children: Vec<Element>) -> Element {
+    Element {
+        tag: tag.to_string(),
+        attributes: attributes
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect(),
+        children,
+        ..Default::default()
+    }
+}
+
+/// The markup of an instance is the one of its component
+fn instance(component: &str, markup: Element) -> Element {
+    Element {
+        component: Some(component.to_string()),
+        ..markup
+    }
+}
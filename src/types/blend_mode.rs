@@ -25,3 +25,28 @@ pub enum BlendMode {
     Color,
     Luminosity,
 }
+
+impl BlendMode {
+    /// CSS blend mode, pass through and normal are the default `normal`.
+    /// CSS has no linear burn and linear dodge, they are approximated with color burn and color dodge.
+    pub fn css(&self) -> &'static str {
+        match self {
+            BlendMode::PassThrough | BlendMode::Normal => "normal",
+            BlendMode::Darken => "darken",
+            BlendMode::Multiply => "multiply",
+            BlendMode::LinearBurn | BlendMode::ColorBurn => "color-burn",
+            BlendMode::Lighten => "lighten",
+            BlendMode::Screen => "screen",
+            BlendMode::LinearDodge | BlendMode::ColorDodge => "color-dodge",
+            BlendMode::Overlay => "overlay",
+            BlendMode::SoftLight => "soft-light",
+            BlendMode::HardLight => "hard-light",
+            BlendMode::Difference => "difference",
+            BlendMode::Exclusion => "exclusion",
+            BlendMode::Hue => "hue",
+            BlendMode::Saturation => "saturation",
+            BlendMode::Color => "color",
+            BlendMode::Luminosity => "luminosity",
+        }
+    }
+}
@@ -13,6 +13,14 @@ impl Colour {
         self.a == 0.0
     }
 
+    /// Folds the opacity of a paint or a layer into the alpha
+    pub fn with_opacity(&self, opacity: f32) -> Colour {
+        Colour {
+            a: self.a * opacity,
+            ..*self
+        }
+    }
+
     pub fn rgba(&self) -> String {
         let red = self.r * 255.0;
         let green = self.g * 255.0;
@@ -20,6 +28,22 @@ impl Colour {
         let alpha = self.a;
         return format!("rgba({red:.0},{green:.0},{blue:.0},{alpha})");
     }
+
+    /// `#rrggbb`, with the alpha as `#rrggbbaa` when the colour is not opaque
+    pub fn hex(&self) -> String {
+        let channel = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+        let hex = format!(
+            "#{:02x}{:02x}{:02x}",
+            channel(self.r),
+            channel(self.g),
+            channel(self.b)
+        );
+
+        match channel(self.a) {
+            255 => hex,
+            alpha => format!("{hex}{alpha:02x}"),
+        }
+    }
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -1,6 +1,14 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
-use super::{blend_mode::BlendMode, colour::Colour, vector::Vector};
+use crate::utils::px;
+
+use super::{
+    blend_mode::BlendMode,
+    colour::Colour,
+    variables::{VariableAlias, Variables},
+    vector::Vector,
+};
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
@@ -26,4 +34,29 @@ pub struct Effect {
     pub offset: Vector,
     #[serde(default)]
     pub spread: f32,
+    #[serde(default)]
+    pub bound_variables: HashMap<String, VariableAlias>,
+}
+
+impl Effect {
+    /// Value of a shadow in `box-shadow`, the bound variables replace its values.
+    /// `colour` is the one of the effect style, when there is one.
+    pub fn shadow(&self, colour: Option<String>, variables: &Variables) -> String {
+        let bound = |field: &str| {
+            self.bound_variables
+                .get(field)
+                .and_then(|alias| variables.var(alias))
+        };
+        let length = |field: &str, value: f32| bound(field).unwrap_or_else(|| px(value));
+
+        let x = length("offsetX", self.offset.x());
+        let y = length("offsetY", self.offset.y());
+        let radius = length("radius", self.radius);
+        let spread = length("spread", self.spread);
+        let colour = bound("color")
+            .or(colour)
+            .unwrap_or_else(|| self.color.rgba());
+
+        format!("{x} {y} {radius} {spread} {colour}")
+    }
 }
@@ -1,9 +1,20 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Deserialize, Serialize, Debug, Clone)]
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum ExportFormat {
     Jpg,
     Png,
     Svg,
 }
+
+impl ExportFormat {
+    /// Value of the `format` parameter of the images endpoint, also used as file extension
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Jpg => "jpg",
+            ExportFormat::Png => "png",
+            ExportFormat::Svg => "svg",
+        }
+    }
+}
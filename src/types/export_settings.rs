@@ -1,7 +1,12 @@
 use serde::{Deserialize, Serialize};
 
-use super::constraint::Constraint;
+use super::constraint::{Constraint, ConstraintType};
 use super::export_format::ExportFormat;
+use super::rectangle::Rectangle;
+
+// Scale limits of the images endpoint
+const MIN_SCALE: f32 = 0.01;
+const MAX_SCALE: f32 = 4.0;
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct ExportSetting {
@@ -9,3 +14,24 @@ pub struct ExportSetting {
     pub format: ExportFormat,
     pub constraint: Constraint,
 }
+
+impl ExportSetting {
+    /// Scale of the exported image, width and height constraints are relative to the node size.
+    /// Vectors don't need a scale.
+    pub fn scale(&self, bounding_box: Option<&Rectangle>) -> f32 {
+        if self.format == ExportFormat::Svg {
+            return 1.0;
+        }
+
+        let size = match self.constraint.constraint_type {
+            ConstraintType::Scale => return self.constraint.value.clamp(MIN_SCALE, MAX_SCALE),
+            ConstraintType::Width => bounding_box.and_then(|b| b.width),
+            ConstraintType::Height => bounding_box.and_then(|b| b.height),
+        };
+
+        match size {
+            Some(size) if size > 0.0 => (self.constraint.value / size).clamp(MIN_SCALE, MAX_SCALE),
+            _ => 1.0,
+        }
+    }
+}
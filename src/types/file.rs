@@ -10,11 +10,129 @@ use super::{
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct FigmaData {
-    name: String,
-    last_modified: String,
-    version: String,
+    pub name: String,
+    pub last_modified: String,
+    pub version: String,
     pub document: Node,
     pub component_sets: HashMap<String, ComponentSet>,
     pub components: HashMap<String, Component>,
     pub styles: HashMap<String, Style>,
 }
+
+impl FigmaData {
+    /// Replaces the top level nodes of the pages with the subtrees from the `/nodes` endpoint.
+    pub fn merge_nodes(&mut self, file_nodes: FileNodes) {
+        let mut documents: HashMap<String, Node> = HashMap::new();
+
+        for (id, data) in file_nodes.nodes {
+            if let Some(data) = data {
+                self.components.extend(data.components);
+                self.component_sets.extend(data.component_sets);
+                self.styles.extend(data.styles);
+                documents.insert(id, data.document);
+            }
+        }
+
+        for page in self.document.common_mut().children.iter_mut() {
+            for node in page.common_mut().children.iter_mut() {
+                if let Some(document) = documents.remove(&node.common().id) {
+                    *node = document;
+                }
+            }
+        }
+    }
+}
+
+/// File metadata, used to check if the cached file is still up to date
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileMeta {
+    pub name: String,
+    pub last_modified: String,
+    pub version: String,
+}
+
+/// Response of the `/v1/files/:key/nodes` endpoint
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileNodes {
+    pub nodes: HashMap<String, Option<NodeData>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeData {
+    pub document: Node,
+    #[serde(default)]
+    pub component_sets: HashMap<String, ComponentSet>,
+    #[serde(default)]
+    pub components: HashMap<String, Component>,
+    #[serde(default)]
+    pub styles: HashMap<String, Style>,
+}
+
+/// Response of the `/v1/images/:key` endpoint, the url is null when the node could not be rendered
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Images {
+    pub err: Option<String>,
+    #[serde(default)]
+    pub images: HashMap<String, Option<String>>,
+}
+
+/// Response of the `/v1/files/:key/images` endpoint, download urls of the image fills by image ref
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImageFills {
+    #[serde(default)]
+    pub error: bool,
+    pub meta: ImageFillsMeta,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImageFillsMeta {
+    #[serde(default)]
+    pub images: HashMap<String, String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merge_nodes() {
+        let mut data: FigmaData = serde_json::from_str(
+            r#"{
+                "name": "File", "lastModified": "2024-01-01T00:00:00Z", "version": "1",
+                "document": { "type": "DOCUMENT", "id": "0:0", "name": "Document", "children": [
+                    { "type": "CANVAS", "id": "0:1", "name": "Page", "children": [
+                        { "type": "CANVAS", "id": "1:1", "name": "Shallow" },
+                        { "type": "CANVAS", "id": "1:2", "name": "Untouched" }
+                    ]}
+                ]},
+                "componentSets": {}, "components": {}, "styles": {}
+            }"#,
+        )
+        .unwrap();
+
+        let nodes: FileNodes = serde_json::from_str(
+            r#"{ "nodes": {
+                "1:1": {
+                    "document": { "type": "CANVAS", "id": "1:1", "name": "Full", "children": [
+                        { "type": "CANVAS", "id": "2:1", "name": "Child" }
+                    ]},
+                    "components": { "2:1": { "key": "k", "name": "Child", "description": "" } }
+                },
+                "9:9": null
+            }}"#,
+        )
+        .unwrap();
+
+        data.merge_nodes(nodes);
+
+        let page = &data.document.common().children[0];
+        assert_eq!(page.common().children[0].common().name, "Full");
+        assert_eq!(page.common().children[0].common().children.len(), 1);
+        assert_eq!(page.common().children[1].common().name, "Untouched");
+        assert!(data.components.contains_key("2:1"));
+    }
+}
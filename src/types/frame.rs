@@ -1,25 +1,28 @@
 use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, HashMap};
 
-use crate::utils::{default_effects, parse_name};
+use crate::utils::{assets::ImageFile, css_opacity, default_effects, parse_name, px};
 
 use super::layout::AxisSizingMode;
-use super::token::Token;
+use super::token::{token_id, Token};
 use super::{
     blend_mode::BlendMode,
+    easing_type::EasingType,
     effect::{Effect, EffectType},
     export_settings::ExportSetting,
     layout::{
         LayoutAlign, LayoutAlignContent, LayoutAlignItems, LayoutConstraint, LayoutGrid,
-        LayoutMode, LayoutPositioning, LayoutSizingMode, LayoutWrap,
+        LayoutGridAlignment, LayoutGridPattern, LayoutMode, LayoutPositioning, LayoutSizingMode,
+        LayoutWrap,
     },
     node_common::NodeCommon,
     overflow_direction::OverflowDirection,
-    paint::Paint,
+    paint::{self, Paint},
     rectangle::Rectangle,
     stroke_align::StrokeAlign,
     stroke_weights::StrokeWeights,
-    transform::Transform,
+    transform::{self, Decomposed, Transform},
+    variables::{BoundVariable, Variables},
     vector::Vector,
 };
 
@@ -111,6 +114,11 @@ pub struct Frame {
     // #[serde(default)]
     // pub is_mask_outline: bool,
     pub styles: Option<HashMap<String, String>>,
+    pub transition_node_id: Option<String>,
+    pub transition_duration: Option<f32>,
+    pub transition_easing: Option<EasingType>,
+    #[serde(default)]
+    pub bound_variables: HashMap<String, BoundVariable>,
 }
 
 impl Frame {
@@ -207,77 +215,56 @@ impl Frame {
         String::new()
     }
 
-    pub fn get_markup_attributes(&self, variant_classes: String, name: String) -> String {
-        let mut current_attributes_classes = String::new();
-
-        if name.contains(",") {
-            let variants: Vec<&str> = name.split(",").map(|v| v.trim()).collect();
-            let mut classes = variant_classes;
-            let mut attributes = String::new();
+    /// Class and variant attributes of the element, ex: `[("class", "button primary"), ("size", "large")]`
+    pub fn get_markup_attributes(
+        &self,
+        variant_classes: String,
+        name: String,
+    ) -> Vec<(String, String)> {
+        if !name.contains("=") {
+            return vec![("class".to_string(), self.get_name())];
+        }
 
-            for variant in variants {
-                let (variant_classes, variant_attributes) =
-                    &self.create_variant_attributes_classes(variant);
-                classes.push_str(&format!(" {variant_classes}"));
-                attributes.push_str(&variant_attributes);
-            }
-            current_attributes_classes = format!(" class=\"{}\"{attributes}", classes.trim());
-        } else if name.contains("=") {
-            let mut classes = variant_classes;
-            let (variant_classes, attributes) = &self.create_variant_attributes_classes(&name);
-            classes.push_str(&format!(" {variant_classes}"));
-            current_attributes_classes = format!(" class=\"{}\"{attributes}", classes.trim());
-        } else {
-            current_attributes_classes = format!(" class=\"{}\"", self.get_name());
+        let mut classes = variant_classes;
+        for variant in name.split(",").map(|v| v.trim()) {
+            classes.push_str(&format!(" {}", self.create_variant_class(variant)));
         }
 
-        current_attributes_classes
+        let mut attributes = vec![(
+            "class".to_string(),
+            classes.split_whitespace().collect::<Vec<&str>>().join(" "),
+        )];
+        attributes.extend(self.variant_attributes(&name));
+        attributes
     }
 
-    fn create_variant_attributes_classes(&self, variant: &str) -> (String, String) {
-        if let Some((first, last)) = variant.split_once("=") {
-            let attribute = parse_name(&first.to_string());
+    fn create_variant_class(&self, variant: &str) -> String {
+        if let Some((_, last)) = variant.split_once("=") {
             let value = parse_name(&last.to_string());
 
-            if let Some((val, second)) = value.split_once(";") {
-                let val = if val.eq("default") {
-                    String::new()
-                } else {
-                    parse_name(&val.to_string())
-                };
-
-                let attr = if PSEUDO_CLASSES.contains(&attribute.as_str()) {
-                    String::new()
-                } else {
-                    format!(" {attribute}=\"{val}\"")
-                };
-
+            if let Some((_, second)) = value.split_once(";") {
                 if !PSEUDO_CLASSES.contains(&second) {
-                    let cl = format!("{}", parse_name(&second.to_string()));
-                    return (cl, attr);
+                    return parse_name(&second.to_string());
                 }
-                return (String::new(), attr);
-            }
-            if !PSEUDO_CLASSES.contains(&value.as_str()) {
-                let val = if value.eq("default") {
-                    String::new()
-                } else {
-                    value
-                };
-
-                let attr = if PSEUDO_CLASSES.contains(&attribute.as_str()) {
-                    String::new()
-                } else {
-                    format!(" {attribute}=\"{val}\"")
-                };
-
-                return (String::new(), attr);
             }
         }
-        (String::new(), String::new())
+        String::new()
+    }
+
+    /// Attributes of the variant properties in the name, ex: `size=large, state=hover` is `[("size", "large")]`
+    pub fn variant_attributes(&self, name: &str) -> Vec<(String, String)> {
+        name.split(",")
+            .filter_map(|v| variant_attribute(v.trim()))
+            .collect()
     }
 
-    pub fn css(&self, parent: Frame, tokens: &HashMap<String, Token>) -> BTreeMap<String, String> {
+    /// `images` are the downloaded image fills by image ref, relative to the `assets/` folder
+    pub fn css(
+        &self,
+        parent: Frame,
+        tokens: &HashMap<String, Token>,
+        images: &HashMap<String, ImageFile>,
+    ) -> BTreeMap<String, String> {
         let mut rules: BTreeMap<String, String> = BTreeMap::new();
 
         if !self.node.visible {
@@ -294,6 +281,13 @@ impl Frame {
             }
         }
 
+        let position = self.position(&parent);
+        if !position.is_empty() {
+            rules.extend(position);
+        } else if self.positions_children() {
+            rules.insert("position".to_string(), "relative".to_string());
+        }
+
         if self.layout_mode.is_auto_layout() {
             if self.node.visible {
                 rules.insert("display".to_string(), "flex".to_string());
@@ -313,8 +307,9 @@ impl Frame {
                 }
             }
 
-            if !self.gap().is_empty() {
-                rules.insert("gap".to_string(), self.gap());
+            let gap = self.gap(&Variables::default());
+            if !gap.is_empty() {
+                rules.insert("gap".to_string(), gap);
             }
 
             if !self.padding().is_empty() {
@@ -322,23 +317,24 @@ impl Frame {
             }
         }
 
-        // Rotation only works well for 90 * n degrees, for other values like 45deg figma changes the sizes of width and height.
-        if !self.rotation().is_empty() {
-            rules.insert("transform".to_string(), self.rotation());
+        rules.extend(self.grid(tokens));
+
+        // The box keeps the size of the node and is transformed around its center,
+        // which stays at the center of the bounding box
+        if !self.transform().is_empty() {
+            rules.insert("transform".to_string(), self.transform());
+            rules.insert("transform-origin".to_string(), "center".to_string());
         }
 
         if !self.border_radius().is_empty() {
             rules.insert("border-radius".to_string(), self.border_radius());
         }
 
-        if !self.border().is_empty() {
-            for (key, value) in self.border().iter() {
-                rules.insert(key.to_string(), value.to_string());
-            }
-        }
+        rules.extend(self.border(&Variables::default()));
 
-        if !self.background().is_empty() {
-            let mut background = self.background();
+        let (mut background, background_blend_mode) =
+            self.background_layers(images, &Variables::default());
+        if !background.is_empty() {
             if let Some(s) = &self.styles {
                 if let Some(token) = tokens.get(&s.get("fills").unwrap_or(&background).to_string())
                 {
@@ -348,6 +344,10 @@ impl Frame {
             rules.insert("background".to_string(), background);
         }
 
+        if !background_blend_mode.is_empty() {
+            rules.insert("background-blend-mode".to_string(), background_blend_mode);
+        }
+
         if !self.box_shadow(None).is_empty() {
             let mut box_shadow_colour = None;
             if let Some(s) = &self.styles {
@@ -368,13 +368,332 @@ impl Frame {
             rules.insert("backdrop-filter".to_string(), self.background_blur());
         }
 
+        if !self.opacity().is_empty() {
+            rules.insert("opacity".to_string(), self.opacity());
+        }
+
+        if !self.mix_blend_mode().is_empty() {
+            rules.insert("mix-blend-mode".to_string(), self.mix_blend_mode());
+        }
+
         rules
     }
 
+    /// Replaces the values bound to Figma variables by `var()` references, for the properties in `rules`
+    pub fn bind_variables(
+        &self,
+        rules: &mut BTreeMap<String, String>,
+        variables: &Variables,
+        images: &HashMap<String, ImageFile>,
+    ) {
+        let bound = |field: &str| self.variable(field, variables);
+
+        for (field, property) in [
+            ("width", "width"),
+            ("height", "height"),
+            ("minWidth", "min-width"),
+            ("maxWidth", "max-width"),
+            ("minHeight", "min-height"),
+            ("maxHeight", "max-height"),
+            ("opacity", "opacity"),
+        ] {
+            // Hug and fill sizes don't use the value
+            if let (Some(rule), Some(value)) = (rules.get_mut(property), bound(field)) {
+                if property == "opacity" || rule.ends_with("px") {
+                    *rule = value;
+                }
+            }
+        }
+
+        // Shorthands are written out when one of the sides is bound
+        let radius = self.corner_radius.unwrap_or(0.0);
+        let radii = self.rectangle_corner_radii.unwrap_or([radius; 4]);
+        let shorthands = [
+            (
+                "padding",
+                [
+                    ("paddingTop", self.padding_top),
+                    ("paddingRight", self.padding_right),
+                    ("paddingBottom", self.padding_bottom),
+                    ("paddingLeft", self.padding_left),
+                ],
+            ),
+            (
+                "border-radius",
+                [
+                    ("topLeftRadius", radii[0]),
+                    ("topRightRadius", radii[1]),
+                    ("bottomRightRadius", radii[2]),
+                    ("bottomLeftRadius", radii[3]),
+                ],
+            ),
+        ];
+        for (property, sides) in shorthands {
+            if rules.contains_key(property) && sides.iter().any(|(field, _)| bound(field).is_some())
+            {
+                let values: Vec<String> = sides
+                    .iter()
+                    .map(|(field, value)| self.length(field, *value, variables))
+                    .collect();
+                rules.insert(property.to_string(), values.join(" "));
+            }
+        }
+
+        if rules.contains_key("gap") {
+            rules.insert("gap".to_string(), self.gap(variables));
+        }
+
+        let borders = self.border(variables);
+        for (property, value) in rules.iter_mut() {
+            if let Some(border) = borders.get(property) {
+                *value = border.clone();
+            }
+        }
+
+        let bound_effects = self
+            .effects
+            .iter()
+            .any(|e| e.visible && !e.bound_variables.is_empty());
+        if rules.contains_key("box-shadow") && bound_effects {
+            rules.insert("box-shadow".to_string(), self.shadows(None, variables));
+        }
+
+        if rules.contains_key("background") && self.fills.iter().any(Paint::is_bound) {
+            let (background, _) = self.background_layers(images, variables);
+            rules.insert("background".to_string(), background);
+        }
+    }
+
+    /// `var()` of the variable bound to the field
+    fn variable(&self, field: &str, variables: &Variables) -> Option<String> {
+        self.bound_variables
+            .get(field)
+            .and_then(BoundVariable::alias)
+            .and_then(|alias| variables.var(alias))
+    }
+
+    /// Length of the field in pixels, or the `var()` of its bound variable
+    fn length(&self, field: &str, value: f32, variables: &Variables) -> String {
+        self.variable(field, variables).unwrap_or_else(|| px(value))
+    }
+
+    /// Children of frames without auto layout, and absolute children of auto layouts,
+    /// are positioned where the designer put them.
+    pub fn positions_child(&self, positioning: Option<&LayoutPositioning>) -> bool {
+        self.absolute_bounding_box.is_some()
+            && !self.is_grid()
+            && (self.layout_mode.is_none()
+                || matches!(positioning, Some(LayoutPositioning::Absolute)))
+    }
+
+    fn positions_children(&self) -> bool {
+        self.node
+            .children
+            .iter()
+            .any(|child| self.positions_child(child.layout_positioning()))
+    }
+
+    fn position(&self, parent: &Frame) -> BTreeMap<String, String> {
+        // Variants are laid out by the component set markup
+        if self.is_variant() {
+            return BTreeMap::new();
+        }
+
+        if parent.is_grid() {
+            return parent.grid_area(self.bounds().as_ref());
+        }
+
+        if !parent.positions_child(self.layout_positioning.as_ref()) {
+            return BTreeMap::new();
+        }
+
+        match (self.bounds(), &parent.absolute_bounding_box) {
+            (Some(bounds), Some(parent_bounds)) => {
+                self.constraints
+                    .position(&bounds, self.relative_transform.as_ref(), parent_bounds)
+            }
+            _ => BTreeMap::new(),
+        }
+    }
+
+    /// First visible columns (or rows) grid
+    fn layout_grid(&self, columns: bool) -> Option<&LayoutGrid> {
+        self.layout_grids.iter().flatten().find(|grid| {
+            grid.is_layout() && matches!(grid.pattern, LayoutGridPattern::Columns) == columns
+        })
+    }
+
+    /// Frames without auto layout lay out their children on their columns and rows grids
+    pub fn is_grid(&self) -> bool {
+        self.layout_mode.is_none()
+            && (self.layout_grid(true).is_some() || self.layout_grid(false).is_some())
+    }
+
+    /// Values of the columns and rows grids, by token name suffix
+    pub fn grid_tokens(&self) -> Vec<(&'static str, String)> {
+        let mut values = vec![];
+
+        for (columns, [tracks, gutter, margin]) in [
+            (true, ["columns", "gutter", "margin"]),
+            (false, ["rows", "row-gutter", "row-margin"]),
+        ] {
+            if let Some(grid) = self.layout_grid(columns) {
+                values.push((tracks, grid.tracks()));
+                values.push((gutter, grid.gutter()));
+                if grid.offset != 0.0 {
+                    values.push((margin, grid.margin()));
+                }
+            }
+        }
+
+        values
+    }
+
+    /// `display: grid` rules, using the grid style tokens when there are some
+    fn grid(&self, tokens: &HashMap<String, Token>) -> BTreeMap<String, String> {
+        let mut rules = BTreeMap::new();
+        if !self.is_grid() {
+            return rules;
+        }
+
+        let style = self.styles.as_ref().and_then(|s| s.get("grid"));
+        let value = |suffix: &str, value: String| match style
+            .and_then(|id| tokens.get(&token_id(id, suffix)))
+        {
+            Some(token) => format!("var({})", token.variable),
+            None => value,
+        };
+
+        if self.node.visible {
+            rules.insert("display".to_string(), "grid".to_string());
+        }
+
+        for (columns, [tracks, gutter, margin]) in [
+            (true, ["columns", "gutter", "margin"]),
+            (false, ["rows", "row-gutter", "row-margin"]),
+        ] {
+            let Some(grid) = self.layout_grid(columns) else {
+                continue;
+            };
+            let (axis, start, end) = if columns {
+                ("column", "padding-left", "padding-right")
+            } else {
+                ("row", "padding-top", "padding-bottom")
+            };
+
+            rules.insert(
+                format!("grid-template-{axis}s"),
+                value(tracks, grid.tracks()),
+            );
+            rules.insert(format!("{axis}-gap"), value(gutter, grid.gutter()));
+
+            let content = if columns {
+                "justify-content"
+            } else {
+                "align-content"
+            };
+            if !matches!(grid.alignment, LayoutGridAlignment::Stretch) {
+                rules.insert(content.to_string(), grid.content_alignment().to_string());
+            }
+
+            if grid.offset != 0.0 {
+                let margin = value(margin, grid.margin());
+                match grid.alignment {
+                    LayoutGridAlignment::Stretch => {
+                        rules.insert(start.to_string(), margin.clone());
+                        rules.insert(end.to_string(), margin);
+                    }
+                    LayoutGridAlignment::Min => {
+                        rules.insert(start.to_string(), margin);
+                    }
+                    LayoutGridAlignment::Max => {
+                        rules.insert(end.to_string(), margin);
+                    }
+                    LayoutGridAlignment::Center => {}
+                }
+            }
+        }
+
+        rules
+    }
+
+    /// `grid-column` and `grid-row` of a child, from the tracks its bounds cover
+    pub fn grid_area(&self, bounds: Option<&Rectangle>) -> BTreeMap<String, String> {
+        let mut rules = BTreeMap::new();
+        let (Some(bounds), Some(parent)) = (bounds, &self.absolute_bounding_box) else {
+            return rules;
+        };
+
+        let offset =
+            |value: Option<f32>, parent: Option<f32>| value.unwrap_or(0.0) - parent.unwrap_or(0.0);
+        let areas = [
+            (
+                "grid-column",
+                self.layout_grid(true),
+                offset(bounds.x, parent.x),
+                bounds.width,
+                parent.width,
+            ),
+            (
+                "grid-row",
+                self.layout_grid(false),
+                offset(bounds.y, parent.y),
+                bounds.height,
+                parent.height,
+            ),
+        ];
+
+        for (property, grid, start, size, total) in areas {
+            if let (Some(grid), Some(size), Some(total)) = (grid, size, total) {
+                if let Some((track, span)) = grid.span(start, size, total) {
+                    rules.insert(property.to_string(), format!("{track} / span {span}"));
+                }
+            }
+        }
+
+        rules
+    }
+
+    fn opacity(&self) -> String {
+        css_opacity(self.opacity.unwrap_or(1.0))
+    }
+
+    fn mix_blend_mode(&self) -> String {
+        match self.blend_mode.as_ref().map(|b| b.css()) {
+            Some("normal") | None => String::new(),
+            Some(blend_mode) => blend_mode.to_string(),
+        }
+    }
+
+    /// Box of the node before its transform: the bounding box grows when a node is rotated or skewed,
+    /// so transformed nodes use their `size` instead.
+    fn bounds(&self) -> Option<Rectangle> {
+        let bounds = self.absolute_bounding_box?;
+
+        match (self.decomposed_transform(), &self.size) {
+            (Some(transform), Some(size)) if !transform.is_identity() => {
+                Some(transform.untransformed_bounds(&bounds, size))
+            }
+            _ => Some(bounds),
+        }
+    }
+
+    fn decomposed_transform(&self) -> Option<Decomposed> {
+        self.relative_transform.as_ref().map(transform::decompose)
+    }
+
+    /// CSS transform from the relative transform, or from the rotation when the file doesn't have it
+    fn transform(&self) -> String {
+        match self.decomposed_transform() {
+            Some(transform) => transform.css(),
+            None => self.rotation(),
+        }
+    }
+
     fn width(&self) -> String {
-        match self.absolute_bounding_box {
+        match self.bounds() {
             Some(rec) => match rec.width {
-                Some(w) => format!("{}px", w),
+                Some(w) => px(w),
                 None => String::new(),
             },
             None => String::new(),
@@ -382,9 +701,9 @@ impl Frame {
     }
 
     fn height(&self) -> String {
-        match self.absolute_bounding_box {
+        match self.bounds() {
             Some(rec) => match rec.height {
-                Some(h) => format!("{}px", h),
+                Some(h) => px(h),
                 None => String::new(),
             },
             None => String::new(),
@@ -403,20 +722,32 @@ impl Frame {
         return String::new();
     }
 
+    /// Background of the fills, without the image fills
     pub fn background(&self) -> String {
-        for paint in self.fills.iter() {
-            if paint.visible && paint.data.get_solid().is_some() {
-                // TODO: get colours, maybe move this logic to get_solid
-                // TODO: build string for when there's multiple backgrounds
-                // Multiple Solid backgrounds converts to a linear gradient, for now we select the first one passing the condition.
-                return match paint.data.get_solid() {
-                    Some(c) => c.rgba(),
-                    None => String::new(),
-                };
-            }
-        }
+        self.background_layers(&HashMap::new(), &Variables::default())
+            .0
+    }
 
-        return String::new();
+    /// `background` and `background-blend-mode` values of the stacked fills
+    fn background_layers(
+        &self,
+        images: &HashMap<String, ImageFile>,
+        variables: &Variables,
+    ) -> (String, String) {
+        let (width, height) = self.size_px();
+        paint::background(&self.fills, width, height, images, variables)
+    }
+
+    /// Width and height of the box, gradients use relative sizes without it
+    fn size_px(&self) -> (f32, f32) {
+        match self.bounds() {
+            Some(Rectangle {
+                width: Some(width),
+                height: Some(height),
+                ..
+            }) if width > 0.0 && height > 0.0 => (width, height),
+            _ => (1.0, 1.0),
+        }
     }
 
     fn rotation(&self) -> String {
@@ -434,27 +765,31 @@ impl Frame {
         }
     }
 
-    fn border(&self) -> HashMap<String, String> {
+    fn border(&self, variables: &Variables) -> HashMap<String, String> {
         // TODO: when multiple colours and sizes convert into "border-width", "border-color" and "border-style"
-        if !self.border_individual().is_empty() {
-            return self.border_individual();
-        } else if !self.border_all().is_empty() {
-            return self.border_all();
+        if !self.border_individual(variables).is_empty() {
+            return self.border_individual(variables);
+        } else if !self.border_all(variables).is_empty() {
+            return self.border_all(variables);
         }
 
         return HashMap::new();
     }
 
     pub fn box_shadow(&self, box_shadow_colour: Option<String>) -> String {
+        self.shadows(box_shadow_colour, &Variables::default())
+    }
+
+    fn shadows(&self, box_shadow_colour: Option<String>, variables: &Variables) -> String {
         let effect_list: Vec<String> = self
             .effects
             .iter()
             .filter(|x| x.visible)
             .map(|e| match e.effect_type {
                 EffectType::InnerShadow => {
-                    format!("inset {}", Frame::shadow(e, box_shadow_colour.clone()))
+                    format!("inset {}", e.shadow(box_shadow_colour.clone(), variables))
                 }
-                EffectType::DropShadow => Frame::shadow(e, box_shadow_colour.clone()),
+                EffectType::DropShadow => e.shadow(box_shadow_colour.clone(), variables),
                 _ => String::new(),
             })
             .collect();
@@ -468,7 +803,7 @@ impl Frame {
             .iter()
             .filter(|x| x.visible)
             .map(|e| match e.effect_type {
-                EffectType::LayerBlur => format!("blur({:0}px)", e.radius),
+                EffectType::LayerBlur => format!("blur({})", px(e.radius)),
                 _ => String::new(),
             })
             .collect();
@@ -485,7 +820,7 @@ impl Frame {
             .iter()
             .filter(|x| x.visible)
             .map(|e| match e.effect_type {
-                EffectType::BackgroundBlur => format!("blur({:0}px)", e.radius),
+                EffectType::BackgroundBlur => format!("blur({})", px(e.radius)),
                 _ => String::new(),
             })
             .collect();
@@ -541,10 +876,19 @@ impl Frame {
         }
     }
 
-    fn gap(&self) -> String {
-        match self.item_spacing {
-            Some(x) => format!("{}px", x),
-            None => String::new(),
+    /// Gap between the items, and between the rows of a wrapped layout first
+    fn gap(&self, variables: &Variables) -> String {
+        let Some(item_spacing) = self.item_spacing else {
+            return String::new();
+        };
+        let gap = self.length("itemSpacing", item_spacing, variables);
+
+        match (&self.layout_wrap, self.counter_axis_spacing) {
+            (LayoutWrap::Wrap, Some(spacing)) => {
+                let row_gap = self.length("counterAxisSpacing", spacing, variables);
+                format!("{row_gap} {gap}")
+            }
+            _ => gap,
         }
     }
 
@@ -556,13 +900,13 @@ impl Frame {
         let left = self.padding_left;
 
         if top == bottom && right == left && top == right {
-            format!("{top}px")
+            px(top)
         } else if top == bottom && right == left {
-            format!("{top}px {right}px")
+            format!("{} {}", px(top), px(right))
         } else if right == left {
-            format!("{top}px {right}px {bottom}px")
+            format!("{} {} {}", px(top), px(right), px(bottom))
         } else {
-            format!("{top}px {right}px {bottom}px {left}px")
+            format!("{} {} {} {}", px(top), px(right), px(bottom), px(left))
         }
     }
 
@@ -570,19 +914,19 @@ impl Frame {
         let mut styles: HashMap<String, String> = HashMap::new();
 
         if let Some(x) = self.min_width {
-            styles.insert("min-width".to_string(), format!("{}px", x));
+            styles.insert("min-width".to_string(), px(x));
         }
 
         if let Some(x) = self.max_width {
-            styles.insert("max-width".to_string(), format!("{}px", x));
+            styles.insert("max-width".to_string(), px(x));
         }
 
         if let Some(x) = self.min_height {
-            styles.insert("min-height".to_string(), format!("{}px", x));
+            styles.insert("min-height".to_string(), px(x));
         }
 
         if let Some(x) = self.max_height {
-            styles.insert("max-height".to_string(), format!("{}px", x));
+            styles.insert("max-height".to_string(), px(x));
         }
 
         if self.layout_mode.is_none() {
@@ -656,7 +1000,7 @@ impl Frame {
 
     fn corner_radius(&self) -> String {
         match self.corner_radius {
-            Some(x) => format!("{}px", x),
+            Some(x) => px(x),
             None => String::new(),
         }
     }
@@ -665,13 +1009,16 @@ impl Frame {
         match self.rectangle_corner_radii {
             Some([top_left, top_right, bottom_right, bottom_left]) => {
                 if top_left == bottom_right && top_right == bottom_left {
-                    format!("{}px {}px", top_left, top_right)
+                    format!("{} {}", px(top_left), px(top_right))
                 } else if top_right == bottom_left {
-                    format!("{}px {}px {}px", top_left, top_right, bottom_right)
+                    format!("{} {} {}", px(top_left), px(top_right), px(bottom_right))
                 } else {
                     format!(
-                        "{}px {}px {}px {}px",
-                        top_left, top_right, bottom_right, bottom_left
+                        "{} {} {} {}",
+                        px(top_left),
+                        px(top_right),
+                        px(bottom_right),
+                        px(bottom_left)
                     )
                 }
             }
@@ -701,16 +1048,25 @@ impl Frame {
         String::new()
     }
 
-    fn border_all(&self) -> HashMap<String, String> {
+    /// Colour of the border, the `var()` of the colour variable bound to the stroke
+    fn stroke_colour(&self, variables: &Variables) -> String {
+        self.strokes
+            .iter()
+            .find(|paint| paint.visible && paint.data.get_solid().is_some())
+            .and_then(|paint| paint.colour_variable(variables))
+            .unwrap_or_else(|| self.border_colour())
+    }
+
+    fn border_all(&self, variables: &Variables) -> HashMap<String, String> {
         let mut borders: HashMap<String, String> = HashMap::new();
 
         let width = match self.stroke_weight {
-            Some(x) => format!("{}px", x),
+            Some(x) => self.length("strokeWeight", x, variables),
             None => String::new(),
         };
 
         let style = self.border_style();
-        let colour = self.border_colour();
+        let colour = self.stroke_colour(variables);
 
         if !width.is_empty() & !colour.is_empty() {
             borders.insert("border".to_string(), format!("{width} {style} {colour}"));
@@ -720,37 +1076,24 @@ impl Frame {
         }
     }
 
-    fn border_individual(&self) -> HashMap<String, String> {
+    fn border_individual(&self, variables: &Variables) -> HashMap<String, String> {
         let mut borders: HashMap<String, String> = HashMap::new();
 
         let style = self.border_style();
-        let colour = self.border_colour();
+        let colour = self.stroke_colour(variables);
 
         match self.individual_stroke_weights {
             Some(border) => {
-                if border.top > 0.0 && !colour.is_empty() {
-                    borders.insert(
-                        "border-top".to_string(),
-                        format!("{}px {} {}", border.top, style, colour),
-                    );
-                }
-                if border.right > 0.0 && !colour.is_empty() {
-                    borders.insert(
-                        "border-right".to_string(),
-                        format!("{}px {} {}", border.right, style, colour),
-                    );
-                }
-                if border.bottom > 0.0 && !colour.is_empty() {
-                    borders.insert(
-                        "border-bottom".to_string(),
-                        format!("{}px {} {}", border.bottom, style, colour),
-                    );
-                }
-                if border.left > 0.0 && !colour.is_empty() {
-                    borders.insert(
-                        "border-left".to_string(),
-                        format!("{}px {} {}", border.left, style, colour),
-                    );
+                for (property, field, weight) in [
+                    ("border-top", "strokeTopWeight", border.top),
+                    ("border-right", "strokeRightWeight", border.right),
+                    ("border-bottom", "strokeBottomWeight", border.bottom),
+                    ("border-left", "strokeLeftWeight", border.left),
+                ] {
+                    if weight > 0.0 && !colour.is_empty() {
+                        let width = self.length(field, weight, variables);
+                        borders.insert(property.to_string(), format!("{width} {style} {colour}"));
+                    }
                 }
 
                 borders
@@ -758,29 +1101,38 @@ impl Frame {
             None => HashMap::new(),
         }
     }
+}
 
-    fn shadow(effect: &Effect, box_shadow_colour: Option<String>) -> String {
-        let Effect {
-            offset,
-            spread,
-            radius,
-            color,
-            ..
-        } = effect;
-        let x = offset.x();
-        let y = offset.y();
-        let rgba = if let Some(c) = box_shadow_colour {
-            c
-        } else {
-            color.rgba()
-        };
-        format!("{x:0}px {y:0}px {radius:0}px {spread:0}px {rgba}")
+/// Attribute of a variant property, the `default` value is an empty attribute and pseudo-classes don't have one
+fn variant_attribute(variant: &str) -> Option<(String, String)> {
+    let (first, last) = variant.split_once("=")?;
+    let attribute = parse_name(&first.to_string());
+    let value = parse_name(&last.to_string());
+
+    if PSEUDO_CLASSES.contains(&attribute.as_str()) {
+        return None;
+    }
+
+    let value = match value.split_once(";") {
+        Some((val, _)) => parse_name(&val.to_string()),
+        None if PSEUDO_CLASSES.contains(&value.as_str()) => return None,
+        None => value,
+    };
+
+    if value == "default" {
+        Some((attribute, String::new()))
+    } else {
+        Some((attribute, value))
     }
 }
 
 #[cfg(test)]
 mod frame_tests {
     use super::*;
+    use crate::{
+        test::VARIABLES_FIXTURE,
+        types::{colour::Colour, variables::LocalVariables},
+    };
 
     #[test]
     fn rectangle_corner_radii() {
@@ -844,6 +1196,177 @@ mod frame_tests {
         );
     }
 
+    #[test]
+    fn relative_transform() {
+        // Rotated 45deg: the bounding box is bigger than the node
+        let (sin, cos) = std::f32::consts::FRAC_PI_4.sin_cos();
+        let side = 100.0 * cos + 50.0 * sin;
+        let frame = Frame {
+            relative_transform: Some([
+                [Some(cos), Some(sin), Some(0.0)],
+                [Some(-sin), Some(cos), Some(70.71)],
+            ]),
+            rotation: Some(std::f32::consts::FRAC_PI_4),
+            size: Some(Vector {
+                x: Some(100.0),
+                y: Some(50.0),
+            }),
+            absolute_bounding_box: Some(Rectangle {
+                x: Some(0.0),
+                y: Some(0.0),
+                width: Some(side),
+                height: Some(side),
+            }),
+            ..Frame::default()
+        };
+
+        assert_eq!(frame.transform(), "rotate(-45deg)");
+        assert_eq!(frame.width(), "100px");
+        assert_eq!(frame.height(), "50px");
+        let bounds = frame.bounds().unwrap();
+        assert_eq!(bounds.x.map(f32::round), Some(3.0));
+        assert_eq!(bounds.y.map(f32::round), Some(28.0));
+    }
+
+    #[test]
+    fn layout_grids() {
+        let frame = Frame {
+            absolute_bounding_box: Some(Rectangle {
+                x: Some(100.0),
+                y: Some(0.0),
+                width: Some(1020.0),
+                height: Some(600.0),
+            }),
+            layout_grids: Some(vec![LayoutGrid {
+                pattern: LayoutGridPattern::Columns,
+                section_size: 60.0,
+                visible: true,
+                color: Colour::default(),
+                alignment: LayoutGridAlignment::Stretch,
+                gutter_size: 20.0,
+                offset: 40.0,
+                count: 12,
+            }]),
+            styles: Some(HashMap::from([("grid".to_string(), "S:2".to_string())])),
+            node: NodeCommon {
+                visible: true,
+                ..NodeCommon::default()
+            },
+            ..Frame::default()
+        };
+        assert!(frame.is_grid());
+        assert!(!frame.positions_child(None));
+
+        let tokens = HashMap::from([(
+            token_id("S:2", "columns"),
+            Token {
+                name: "Grid".to_string(),
+                variable: "--grid-columns".to_string(),
+                value: "repeat(12, 1fr)".to_string(),
+                theme: ":root".to_string(),
+                property: None,
+            },
+        )]);
+        let rules = frame.grid(&tokens);
+        assert_eq!(rules["display"], "grid");
+        assert_eq!(rules["grid-template-columns"], "var(--grid-columns)");
+        assert_eq!(rules["column-gap"], "20px");
+        assert_eq!(rules["padding-left"], "40px");
+        assert_eq!(rules["padding-right"], "40px");
+        assert_eq!(
+            frame.grid_tokens(),
+            vec![
+                ("columns", "repeat(12, 1fr)".to_string()),
+                ("gutter", "20px".to_string()),
+                ("margin", "40px".to_string()),
+            ]
+        );
+
+        let child = Rectangle {
+            x: Some(220.0),
+            y: Some(10.0),
+            width: Some(220.0),
+            height: Some(50.0),
+        };
+        let rules = frame.grid_area(Some(&child));
+        assert_eq!(rules["grid-column"], "2 / span 3");
+        assert!(!rules.contains_key("grid-row"));
+    }
+
+    #[test]
+    fn bind_variables() {
+        let variables = serde_json::from_str::<LocalVariables>(VARIABLES_FIXTURE)
+            .unwrap()
+            .meta;
+
+        let frame = Frame {
+            layout_mode: LayoutMode::Horizontal,
+            layout_wrap: LayoutWrap::Wrap,
+            item_spacing: Some(8.0),
+            counter_axis_spacing: Some(16.0),
+            stroke_weight: Some(1.0),
+            strokes: serde_json::from_str(
+                r#"[{ "type": "SOLID", "color": { "r": 0, "g": 0, "b": 0, "a": 1 },
+                      "boundVariables": { "color": { "type": "VARIABLE_ALIAS", "id": "VariableID:1:2" } } }]"#,
+            ).unwrap(),
+            fills: serde_json::from_str(
+                r#"[
+                    { "type": "SOLID", "color": { "r": 1, "g": 1, "b": 1, "a": 1 } },
+                    { "type": "SOLID", "opacity": 0.5, "color": { "r": 0, "g": 0, "b": 0, "a": 1 },
+                      "boundVariables": { "color": { "type": "VARIABLE_ALIAS", "id": "VariableID:1:3" } } }
+                ]"#,
+            ).unwrap(),
+            effects: serde_json::from_str(
+                r#"[{ "type": "DROP_SHADOW", "visible": true, "radius": 4, "offset": { "x": 0, "y": 2 },
+                      "color": { "r": 0, "g": 0, "b": 0, "a": 0.25 },
+                      "boundVariables": { "radius": { "type": "VARIABLE_ALIAS", "id": "VariableID:2:2" } } }]"#,
+            ).unwrap(),
+            bound_variables: serde_json::from_str(
+                r#"{ "counterAxisSpacing": { "type": "VARIABLE_ALIAS", "id": "VariableID:2:2" },
+                     "strokeWeight": { "type": "VARIABLE_ALIAS", "id": "VariableID:2:2" } }"#,
+            ).unwrap(),
+            ..Frame::default()
+        };
+
+        let mut rules = frame.css(Frame::default(), &HashMap::new(), &HashMap::new());
+        assert_eq!(rules["gap"], "16px 8px");
+        frame.bind_variables(&mut rules, &variables, &HashMap::new());
+
+        assert_eq!(rules["gap"], "var(--spacing-medium) 8px");
+        assert_eq!(
+            rules["border"],
+            "var(--spacing-medium) solid var(--brand-blue)"
+        );
+        assert_eq!(
+            rules["box-shadow"],
+            "0px 2px var(--spacing-medium) 0px rgba(0,0,0,0.25)"
+        );
+        assert_eq!(
+            rules["background"],
+            "linear-gradient(color-mix(in srgb, var(--button-background) 50%, transparent), \
+             color-mix(in srgb, var(--button-background) 50%, transparent)), rgba(255,255,255,1)"
+        );
+    }
+
+    #[test]
+    fn opacity_blend_mode() {
+        let frame = Frame {
+            opacity: Some(0.6000001),
+            blend_mode: Some(BlendMode::LinearDodge),
+            ..Frame::default()
+        };
+        assert_eq!(frame.opacity(), "0.6");
+        assert_eq!(frame.mix_blend_mode(), "color-dodge");
+
+        let frame = Frame {
+            opacity: Some(1.0),
+            blend_mode: Some(BlendMode::PassThrough),
+            ..Frame::default()
+        };
+        assert_eq!(frame.opacity(), "");
+        assert_eq!(frame.mix_blend_mode(), "");
+    }
+
     fn get_classes_helper(class: &str) -> String {
         Frame {
             node: NodeCommon {
@@ -904,4 +1427,20 @@ mod frame_tests {
         assert_eq!(get_classes_helper("hover=true"), ":hover");
         assert_eq!(get_classes_helper("hover=false"), "");
     }
+
+    #[test]
+    fn variant_attributes() {
+        let frame = Frame::default();
+        let attribute = |name: &str, value: &str| (name.to_string(), value.to_string());
+
+        assert_eq!(
+            frame.variant_attributes("size=large, state=hover"),
+            vec![attribute("size", "large")]
+        );
+        assert_eq!(
+            frame.variant_attributes("type=default, state=active;primary"),
+            vec![attribute("type", ""), attribute("state", "active")]
+        );
+        assert_eq!(frame.variant_attributes("Button"), vec![]);
+    }
 }
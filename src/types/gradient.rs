@@ -1,11 +1,10 @@
 use serde::{Deserialize, Serialize};
 
-use super::{blend_mode::BlendMode, colour::Colour, vector::Vector};
+use super::{colour::Colour, vector::Vector};
+use crate::utils::round;
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct Gradient {
-    #[serde(default, rename = "blendMode")]
-    pub blend_mode: BlendMode,
     #[serde(rename = "gradientHandlePositions")]
     pub gradient_handle_positions: Vec<Vector>,
     #[serde(rename = "gradientStops")]
@@ -17,3 +16,194 @@ pub struct ColorStop {
     pub position: f32,
     pub color: Colour,
 }
+
+// The handle positions are relative to the node size, (0, 0) is the top left corner and (1, 1) the bottom right one.
+impl Gradient {
+    /// The angle and the stops follow the handles on a `width` x `height` element,
+    /// because css stretches the gradient line to the corners of the element.
+    pub fn linear(&self, width: f32, height: f32) -> String {
+        let Some(((x0, y0), (x1, y1))) = self.handle(0).zip(self.handle(1)) else {
+            return String::new();
+        };
+
+        let (dx, dy) = ((x1 - x0) * width, (y1 - y0) * height);
+        let length = dx.hypot(dy);
+        if length == 0.0 {
+            return String::new();
+        }
+
+        // Unit vector of the gradient direction
+        let (ux, uy) = (dx / length, dy / length);
+        let line = (width * ux).abs() + (height * uy).abs();
+
+        let stops = self.stops(|position| {
+            let x = x0 * width + position * dx - width / 2.0;
+            let y = y0 * height + position * dy - height / 2.0;
+            (x * ux + y * uy) / line + 0.5
+        });
+
+        format!("linear-gradient({}deg, {stops})", round(angle(dx, dy)))
+    }
+
+    /// The first handle is the center, the second and the third ones the ends of the ellipse radii.
+    pub fn radial(&self, width: f32, height: f32) -> String {
+        let Some(((cx, cy), (x1, y1))) = self.handle(0).zip(self.handle(1)) else {
+            return String::new();
+        };
+        let (x2, y2) = self.handle(2).unwrap_or((x1, y1));
+
+        let rx = ((x1 - cx) * width).hypot((y1 - cy) * height) / width;
+        let ry = ((x2 - cx) * width).hypot((y2 - cy) * height) / height;
+
+        format!(
+            "radial-gradient(ellipse {}% {}% at {}% {}%, {})",
+            round(rx * 100.0),
+            round(ry * 100.0),
+            round(cx * 100.0),
+            round(cy * 100.0),
+            self.stops(|position| position)
+        )
+    }
+
+    /// Angular gradients start in the direction of the second handle.
+    pub fn conic(&self, width: f32, height: f32) -> String {
+        let Some(((cx, cy), (x1, y1))) = self.handle(0).zip(self.handle(1)) else {
+            return String::new();
+        };
+
+        format!(
+            "conic-gradient(from {}deg at {}% {}%, {})",
+            round(angle((x1 - cx) * width, (y1 - cy) * height)),
+            round(cx * 100.0),
+            round(cy * 100.0),
+            self.stops(|position| position)
+        )
+    }
+
+    /// CSS has no diamond gradient, it's approximated with a linear gradient from the center to each corner,
+    /// one background layer per quarter of the element, assuming the diamond is centered and fills the element.
+    pub fn diamond(&self) -> Vec<String> {
+        let stops = self.stops(|position| position);
+
+        ["top left", "top right", "bottom right", "bottom left"]
+            .iter()
+            .map(|corner| {
+                format!("linear-gradient(to {corner}, {stops}) {corner} / 50% 50% no-repeat")
+            })
+            .collect()
+    }
+
+    pub fn with_opacity(&self, opacity: f32) -> Gradient {
+        Gradient {
+            gradient_handle_positions: self.gradient_handle_positions.clone(),
+            gradient_stops: self
+                .gradient_stops
+                .iter()
+                .map(|stop| ColorStop {
+                    position: stop.position,
+                    color: stop.color.with_opacity(opacity),
+                })
+                .collect(),
+        }
+    }
+
+    fn handle(&self, index: usize) -> Option<(f32, f32)> {
+        self.gradient_handle_positions
+            .get(index)
+            .map(|handle| (handle.x(), handle.y()))
+    }
+
+    /// Colour stops, `position` converts the position of a stop to the css one, between 0 and 1.
+    fn stops(&self, position: impl Fn(f32) -> f32) -> String {
+        self.gradient_stops
+            .iter()
+            .map(|stop| {
+                format!(
+                    "{} {}%",
+                    stop.color.rgba(),
+                    round(position(stop.position) * 100.0)
+                )
+            })
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+}
+
+/// CSS angle of a direction, clockwise from the top
+fn angle(dx: f32, dy: f32) -> f32 {
+    dx.atan2(-dy).to_degrees().rem_euclid(360.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gradient(handles: &[(f32, f32)]) -> Gradient {
+        Gradient {
+            gradient_handle_positions: handles
+                .iter()
+                .map(|(x, y)| Vector {
+                    x: Some(*x),
+                    y: Some(*y),
+                })
+                .collect(),
+            gradient_stops: vec![
+                ColorStop {
+                    position: 0.0,
+                    color: Colour {
+                        a: 1.0,
+                        ..Colour::default()
+                    },
+                },
+                ColorStop {
+                    position: 1.0,
+                    color: Colour {
+                        a: 1.0,
+                        r: 1.0,
+                        g: 1.0,
+                        b: 1.0,
+                    },
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn linear() {
+        assert_eq!(
+            gradient(&[(0.0, 0.5), (1.0, 0.5), (0.0, 1.0)]).linear(100.0, 50.0),
+            "linear-gradient(90deg, rgba(0,0,0,1) 0%, rgba(255,255,255,1) 100%)"
+        );
+        assert_eq!(
+            gradient(&[(0.5, 0.0), (0.5, 1.0), (0.0, 0.0)]).linear(100.0, 50.0),
+            "linear-gradient(180deg, rgba(0,0,0,1) 0%, rgba(255,255,255,1) 100%)"
+        );
+        // Handles inside the element, the css gradient line goes from edge to edge
+        assert_eq!(
+            gradient(&[(0.5, 0.75), (0.5, 0.25), (0.0, 0.0)]).linear(100.0, 100.0),
+            "linear-gradient(0deg, rgba(0,0,0,1) 25%, rgba(255,255,255,1) 75%)"
+        );
+        assert_eq!(gradient(&[(0.5, 0.5)]).linear(100.0, 100.0), "");
+    }
+
+    #[test]
+    fn radial_conic_diamond() {
+        let radial = gradient(&[(0.5, 0.5), (1.0, 0.5), (0.5, 1.0)]);
+        assert_eq!(
+            radial.radial(200.0, 100.0),
+            "radial-gradient(ellipse 50% 50% at 50% 50%, rgba(0,0,0,1) 0%, rgba(255,255,255,1) 100%)"
+        );
+        assert_eq!(
+            radial.conic(200.0, 100.0),
+            "conic-gradient(from 90deg at 50% 50%, rgba(0,0,0,1) 0%, rgba(255,255,255,1) 100%)"
+        );
+        assert_eq!(
+            radial.diamond()[0],
+            "linear-gradient(to top left, rgba(0,0,0,1) 0%, rgba(255,255,255,1) 100%) top left / 50% 50% no-repeat"
+        );
+        assert_eq!(
+            radial.with_opacity(0.5).linear(100.0, 100.0),
+            "linear-gradient(90deg, rgba(0,0,0,0.5) 50%, rgba(255,255,255,0.5) 100%)"
+        );
+    }
+}
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
-use super::colour::Colour;
+use super::{colour::Colour, rectangle::Rectangle, transform::Transform};
+use crate::utils::{px, round};
 
 #[derive(Deserialize, Serialize, Debug, Clone, Copy, Default)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
@@ -29,6 +31,101 @@ pub struct LayoutConstraint {
     pub horizontal: HorizontalLayoutConstraintValue,
 }
 
+impl LayoutConstraint {
+    /// Offsets and sizes of an absolutely positioned node, following the constraints when the parent is resized.
+    /// The offsets come from the bounding boxes, or from the relative transform when the node has no position.
+    pub fn position(
+        &self,
+        bounds: &Rectangle,
+        relative_transform: Option<&Transform>,
+        parent: &Rectangle,
+    ) -> BTreeMap<String, String> {
+        let mut rules: BTreeMap<String, String> = BTreeMap::new();
+
+        let (Some(width), Some(height), Some(parent_width), Some(parent_height)) =
+            (bounds.width, bounds.height, parent.width, parent.height)
+        else {
+            return rules;
+        };
+
+        let translate = |row: usize| relative_transform.and_then(|t| t[row][2]).unwrap_or(0.0);
+        let x = match (bounds.x, parent.x) {
+            (Some(x), Some(parent_x)) => x - parent_x,
+            _ => translate(0),
+        };
+        let y = match (bounds.y, parent.y) {
+            (Some(y), Some(parent_y)) => y - parent_y,
+            _ => translate(1),
+        };
+
+        let horizontal = match self.horizontal {
+            HorizontalLayoutConstraintValue::Left => [("left", px(x)), ("width", px(width))],
+            HorizontalLayoutConstraintValue::Right => [
+                ("right", px(parent_width - x - width)),
+                ("width", px(width)),
+            ],
+            HorizontalLayoutConstraintValue::Center => {
+                [("left", center(x, parent_width)), ("width", px(width))]
+            }
+            HorizontalLayoutConstraintValue::LeftRight => {
+                [("left", px(x)), ("right", px(parent_width - x - width))]
+            }
+            HorizontalLayoutConstraintValue::Scale => [
+                ("left", percent(x, parent_width)),
+                ("width", percent(width, parent_width)),
+            ],
+        };
+
+        let vertical = match self.vertical {
+            VerticalLayoutConstraintValue::Top => [("top", px(y)), ("height", px(height))],
+            VerticalLayoutConstraintValue::Bottom => [
+                ("bottom", px(parent_height - y - height)),
+                ("height", px(height)),
+            ],
+            VerticalLayoutConstraintValue::Center => {
+                [("top", center(y, parent_height)), ("height", px(height))]
+            }
+            VerticalLayoutConstraintValue::TopBottom => {
+                [("top", px(y)), ("bottom", px(parent_height - y - height))]
+            }
+            VerticalLayoutConstraintValue::Scale => [
+                ("top", percent(y, parent_height)),
+                ("height", percent(height, parent_height)),
+            ],
+        };
+
+        rules.insert("position".to_string(), "absolute".to_string());
+        // Stretched nodes get their size from the offsets
+        rules.insert("width".to_string(), "auto".to_string());
+        rules.insert("height".to_string(), "auto".to_string());
+
+        for (key, value) in horizontal.into_iter().chain(vertical) {
+            rules.insert(key.to_string(), value);
+        }
+
+        rules
+    }
+}
+
+fn percent(value: f32, total: f32) -> String {
+    if total == 0.0 {
+        return "0%".to_string();
+    }
+
+    format!("{}%", round(value / total * 100.0))
+}
+
+/// Offset from the center of the parent, so the node stays centered when the parent is resized
+fn center(offset: f32, total: f32) -> String {
+    let offset = offset - total / 2.0;
+
+    if offset < 0.0 {
+        format!("calc(50% - {})", px(-offset))
+    } else {
+        format!("calc(50% + {})", px(offset))
+    }
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone, Default)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum LayoutAlign {
@@ -171,6 +268,7 @@ pub enum LayoutGridPattern {
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum LayoutGridAlignment {
     Min,
+    Max,
     Stretch,
     Center,
 }
@@ -186,3 +284,166 @@ pub struct LayoutGrid {
     pub offset: f32,
     pub count: i32,
 }
+
+impl LayoutGrid {
+    /// Columns and rows grids lay out the children, square grids are only a guide in Figma
+    pub fn is_layout(&self) -> bool {
+        self.visible && !matches!(self.pattern, LayoutGridPattern::Grid)
+    }
+
+    /// `grid-template-columns` or `grid-template-rows` value, stretched tracks share the space
+    /// and fixed ones keep the section size. Figma uses a negative count for "auto".
+    pub fn tracks(&self) -> String {
+        if self.count <= 0 {
+            return format!("repeat(auto-fill, {})", px(self.section_size));
+        }
+
+        match self.alignment {
+            LayoutGridAlignment::Stretch => format!("repeat({}, 1fr)", self.count),
+            _ => format!("repeat({}, {})", self.count, px(self.section_size)),
+        }
+    }
+
+    pub fn gutter(&self) -> String {
+        px(self.gutter_size)
+    }
+
+    pub fn margin(&self) -> String {
+        px(self.offset)
+    }
+
+    /// `justify-content` or `align-content` value of the tracks
+    pub fn content_alignment(&self) -> &'static str {
+        match self.alignment {
+            LayoutGridAlignment::Min => "start",
+            LayoutGridAlignment::Max => "end",
+            LayoutGridAlignment::Center => "center",
+            LayoutGridAlignment::Stretch => "stretch",
+        }
+    }
+
+    /// First track (starting at 1 like CSS lines) and number of tracks covered by a child
+    /// from `start` with `size`, in a container of `total` size.
+    pub fn span(&self, start: f32, size: f32, total: f32) -> Option<(i32, i32)> {
+        let step = self.section_size + self.gutter_size;
+        if step <= 0.0 {
+            return None;
+        }
+
+        let count = if self.count > 0 {
+            self.count
+        } else {
+            ((total - 2.0 * self.offset + self.gutter_size) / step).floor() as i32
+        };
+        if count <= 0 {
+            return None;
+        }
+
+        let width = count as f32 * step - self.gutter_size;
+        let first = match self.alignment {
+            LayoutGridAlignment::Min | LayoutGridAlignment::Stretch => self.offset,
+            LayoutGridAlignment::Max => total - self.offset - width,
+            LayoutGridAlignment::Center => (total - width) / 2.0 + self.offset,
+        };
+
+        // Edges that are less than a pixel into a track don't cover it
+        let track =
+            |position: f32| (((position - first) / step).floor() as i32).clamp(0, count - 1);
+        let first_track = track(start + 1.0);
+        let last_track = track(start + size - 1.0).max(first_track);
+
+        Some((first_track + 1, last_track - first_track + 1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rectangle(x: f32, y: f32, width: f32, height: f32) -> Rectangle {
+        Rectangle {
+            x: Some(x),
+            y: Some(y),
+            width: Some(width),
+            height: Some(height),
+        }
+    }
+
+    fn grid(alignment: LayoutGridAlignment, count: i32) -> LayoutGrid {
+        LayoutGrid {
+            pattern: LayoutGridPattern::Columns,
+            section_size: 60.0,
+            visible: true,
+            color: Colour::default(),
+            alignment,
+            gutter_size: 20.0,
+            offset: 40.0,
+            count,
+        }
+    }
+
+    #[test]
+    fn layout_grid() {
+        let columns = grid(LayoutGridAlignment::Stretch, 12);
+        assert_eq!(columns.tracks(), "repeat(12, 1fr)");
+        assert_eq!(columns.gutter(), "20px");
+        assert_eq!(columns.margin(), "40px");
+        // Second to fourth columns, the first one starts at the offset
+        assert_eq!(columns.span(120.0, 220.0, 1020.0), Some((2, 3)));
+        assert_eq!(columns.span(0.0, 2000.0, 1020.0), Some((1, 12)));
+
+        let columns = grid(LayoutGridAlignment::Center, -1);
+        assert_eq!(columns.tracks(), "repeat(auto-fill, 60px)");
+        assert_eq!(columns.content_alignment(), "center");
+        // 4 columns, 300px wide in total, centered and moved by the offset: starting at 90
+        assert_eq!(columns.span(80.0, 60.0, 400.0), Some((1, 1)));
+        assert_eq!(columns.span(330.0, 60.0, 400.0), Some((4, 1)));
+    }
+
+    #[test]
+    fn constraints_position() {
+        let parent = rectangle(100.0, 100.0, 200.0, 100.0);
+        let bounds = rectangle(110.0, 120.0, 50.0, 20.0);
+
+        let rules = LayoutConstraint::default().position(&bounds, None, &parent);
+        assert_eq!(rules["position"], "absolute");
+        assert_eq!(rules["left"], "10px");
+        assert_eq!(rules["top"], "20px");
+        assert_eq!(rules["width"], "50px");
+        assert_eq!(rules["height"], "20px");
+
+        let rules = LayoutConstraint {
+            horizontal: HorizontalLayoutConstraintValue::LeftRight,
+            vertical: VerticalLayoutConstraintValue::Bottom,
+        }
+        .position(&bounds, None, &parent);
+        assert_eq!(rules["left"], "10px");
+        assert_eq!(rules["right"], "140px");
+        assert_eq!(rules["width"], "auto");
+        assert_eq!(rules["bottom"], "60px");
+        assert!(!rules.contains_key("top"));
+
+        let rules = LayoutConstraint {
+            horizontal: HorizontalLayoutConstraintValue::Center,
+            vertical: VerticalLayoutConstraintValue::Scale,
+        }
+        .position(&bounds, None, &parent);
+        assert_eq!(rules["left"], "calc(50% - 90px)");
+        assert_eq!(rules["top"], "20%");
+        assert_eq!(rules["height"], "20%");
+
+        // Without the position in the bounding box, the relative transform is used
+        let bounds = Rectangle {
+            x: None,
+            y: None,
+            ..bounds
+        };
+        let transform = [
+            [Some(1.0), Some(0.0), Some(5.0)],
+            [Some(0.0), Some(1.0), Some(6.0)],
+        ];
+        let rules = LayoutConstraint::default().position(&bounds, Some(&transform), &parent);
+        assert_eq!(rules["left"], "5px");
+        assert_eq!(rules["top"], "6px");
+    }
+}
@@ -18,6 +18,7 @@ pub mod overflow_direction;
 pub mod paint;
 pub mod path;
 pub mod rectangle;
+pub mod scale_mode;
 pub mod section;
 pub mod size;
 pub mod stroke_align;
@@ -26,5 +27,6 @@ pub mod styles;
 pub mod text;
 pub mod token;
 pub mod transform;
+pub mod variables;
 pub mod vector;
 pub mod vector_common;
@@ -1,7 +1,7 @@
 use super::{
-    export_settings::ExportSetting, frame::Frame, node_common::NodeCommon, rectangle::Rectangle,
-    section::Section, styles::TypeStyle, transform::Transform, vector::Vector,
-    vector_common::VectorCommon,
+    export_settings::ExportSetting, frame::Frame, layout::LayoutPositioning,
+    node_common::NodeCommon, rectangle::Rectangle, section::Section, styles::TypeStyle,
+    transform::Transform, vector::Vector, vector_common::VectorCommon,
 };
 use serde::{Deserialize, Serialize};
 
@@ -98,6 +98,123 @@ impl Node {
         }
     }
 
+    pub fn common_mut(&mut self) -> &mut NodeCommon {
+        match self {
+            Node::DOCUMENT(node) => node,
+            Node::CANVAS { node, .. } => node,
+            Node::FRAME(Frame { node, .. }) => node,
+            Node::GROUP(Frame { node, .. }) => node,
+            Node::SECTION(Section { node, .. }) => node,
+            Node::VECTOR(VectorCommon { node, .. }) => node,
+            Node::BOOLEAN_OPERATION {
+                vector: VectorCommon { node, .. },
+                ..
+            } => node,
+            Node::STAR(VectorCommon { node, .. }) => node,
+            Node::LINE(VectorCommon { node, .. }) => node,
+            Node::ELLIPSE(VectorCommon { node, .. }) => node,
+            Node::REGULAR_POLYGON(VectorCommon { node, .. }) => node,
+            Node::RECTANGLE {
+                vector: VectorCommon { node, .. },
+                ..
+            } => node,
+            Node::TEXT {
+                vector: VectorCommon { node, .. },
+                ..
+            } => node,
+            Node::SLICE { node, .. } => node,
+            Node::COMPONENT(Frame { node, .. }) => node,
+            Node::COMPONENT_SET(Frame { node, .. }) => node,
+            Node::INSTANCE {
+                frame: Frame { node, .. },
+                ..
+            } => node,
+        }
+    }
+
+    pub fn node_type(&self) -> &'static str {
+        match self {
+            Node::DOCUMENT(_) => "DOCUMENT",
+            Node::CANVAS { .. } => "CANVAS",
+            Node::FRAME(_) => "FRAME",
+            Node::GROUP(_) => "GROUP",
+            Node::SECTION(_) => "SECTION",
+            Node::VECTOR(_) => "VECTOR",
+            Node::BOOLEAN_OPERATION { .. } => "BOOLEAN_OPERATION",
+            Node::STAR(_) => "STAR",
+            Node::LINE(_) => "LINE",
+            Node::ELLIPSE(_) => "ELLIPSE",
+            Node::REGULAR_POLYGON(_) => "REGULAR_POLYGON",
+            Node::RECTANGLE { .. } => "RECTANGLE",
+            Node::TEXT { .. } => "TEXT",
+            Node::SLICE { .. } => "SLICE",
+            Node::COMPONENT(_) => "COMPONENT",
+            Node::COMPONENT_SET(_) => "COMPONENT_SET",
+            Node::INSTANCE { .. } => "INSTANCE",
+        }
+    }
+
+    pub fn is_vector(&self) -> Option<&VectorCommon> {
+        match self {
+            Node::VECTOR(vector)
+            | Node::BOOLEAN_OPERATION { vector, .. }
+            | Node::STAR(vector)
+            | Node::LINE(vector)
+            | Node::ELLIPSE(vector)
+            | Node::REGULAR_POLYGON(vector)
+            | Node::RECTANGLE { vector, .. }
+            | Node::TEXT { vector, .. } => Some(vector),
+            _ => None,
+        }
+    }
+
+    /// Shapes, and frames only made of shapes like the icons, which can be replaced by their exported image
+    pub fn is_graphic(&self) -> bool {
+        match self {
+            Node::TEXT { .. } => false,
+            Node::SLICE { .. } => true,
+            _ if self.is_vector().is_some() => true,
+            _ => self.is_frame().is_some() && self.common().children.iter().all(Node::is_graphic),
+        }
+    }
+
+    pub fn layout_positioning(&self) -> Option<&LayoutPositioning> {
+        match (self.is_frame(), self.is_vector()) {
+            (Some(frame), _) => frame.layout_positioning.as_ref(),
+            (_, Some(vector)) => vector.layout_positioning.as_ref(),
+            _ => None,
+        }
+    }
+
+    pub fn export_settings(&self) -> &[ExportSetting] {
+        let settings = match self {
+            Node::SLICE {
+                export_settings, ..
+            } => return export_settings,
+            _ => match (self.is_frame(), self.is_vector()) {
+                (Some(frame), _) => &frame.export_settings,
+                (_, Some(vector)) => &vector.export_settings,
+                _ => return &[],
+            },
+        };
+
+        settings.as_deref().unwrap_or_default()
+    }
+
+    pub fn bounding_box(&self) -> Option<&Rectangle> {
+        match self {
+            Node::SLICE {
+                absolute_bounding_box,
+                ..
+            } => Some(absolute_bounding_box),
+            _ => match (self.is_frame(), self.is_vector()) {
+                (Some(frame), _) => frame.absolute_bounding_box.as_ref(),
+                (_, Some(vector)) => vector.absolute_bounding_box.as_ref(),
+                _ => None,
+            },
+        }
+    }
+
     pub fn is_component_set(&self) -> Option<&Frame> {
         match self {
             Node::COMPONENT_SET(frame) => Some(frame),
@@ -1,8 +1,20 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
-use crate::utils::{default_opacity, default_visible};
+use crate::utils::{
+    assets::{ImageFile, ASSETS_DIR},
+    default_opacity, default_visible, px, round,
+};
 
-use super::{colour::Colour, gradient::Gradient};
+use super::{
+    blend_mode::BlendMode,
+    colour::Colour,
+    gradient::Gradient,
+    image_filters::ImageFilters,
+    scale_mode::ScaleMode,
+    transform::Transform,
+    variables::{VariableAlias, Variables},
+};
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(tag = "type", rename_all = "SCREAMING_SNAKE_CASE")]
@@ -26,21 +38,22 @@ pub enum PaintData {
         #[serde(flatten)]
         gradient: Gradient,
     },
-    // Image {
-    //     #[serde(rename = "scaleMode")]
-    //     scale_mode: ScaleMode,
-    //     #[serde(rename = "imageTransform", default)]
-    //     image_transform: Option<Transform>, // only if scale_mode is STRETCH
-    //     #[serde(rename = "scalingFactor", default)]
-    //     scaling_factor: Option<f32>, // only if scale_mode is TILE
-    //     #[serde(default)] // not present?
-    //     rotation: f32,
-    //     #[serde(rename = "imageRef")]
-    //     image_ref: Option<String>, // sometimes this appears in the character type mapping table and is null
-    //     #[serde(rename = "gifRef", default)]
-    //     gif_ref: Option<String>,
-    //     filters: Option<ImageFilters>,
-    // },
+    #[serde(rename_all = "camelCase")]
+    Image {
+        #[serde(default)]
+        scale_mode: ScaleMode,
+        #[serde(default)]
+        image_transform: Option<Transform>, // only if scale_mode is STRETCH
+        #[serde(default)]
+        scaling_factor: Option<f32>, // only if scale_mode is TILE
+        /// Degrees, not applied: CSS can't rotate a background image
+        #[serde(default)]
+        rotation: f32,
+        image_ref: Option<String>, // sometimes this appears in the character type mapping table and is null
+        #[serde(default)]
+        gif_ref: Option<String>,
+        filters: Option<ImageFilters>,
+    },
     // Emoji,
 }
 
@@ -52,6 +65,106 @@ impl PaintData {
             _ => None,
         }
     }
+
+    pub fn get_gradient(&self) -> Option<&Gradient> {
+        match self {
+            PaintData::GradientLinear { gradient }
+            | PaintData::GradientRadial { gradient }
+            | PaintData::GradientAngular { gradient }
+            | PaintData::GradientDiamond { gradient } => Some(gradient),
+            _ => None,
+        }
+    }
+
+    /// Background layers of a gradient on a `width` x `height` element, the colours alpha is multiplied by `opacity`
+    pub fn gradient_css(&self, width: f32, height: f32, opacity: f32) -> Vec<String> {
+        let Some(gradient) = self.get_gradient() else {
+            return Vec::new();
+        };
+        let gradient = gradient.with_opacity(opacity);
+
+        let layer = match self {
+            PaintData::GradientLinear { .. } => gradient.linear(width, height),
+            PaintData::GradientRadial { .. } => gradient.radial(width, height),
+            PaintData::GradientAngular { .. } => gradient.conic(width, height),
+            _ => return gradient.diamond(),
+        };
+
+        if layer.is_empty() {
+            Vec::new()
+        } else {
+            vec![layer]
+        }
+    }
+
+    pub fn get_image_ref(&self) -> Option<&String> {
+        match self {
+            PaintData::Image { image_ref, .. } => image_ref.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Background layer of an image fill, `url` is the downloaded image and `image_size` its size in pixels.
+    /// The tiles are the image scaled by the scaling factor, they keep its size when it is unknown.
+    /// The rotation of the image is dropped, the background is drawn unrotated.
+    pub fn image_css(&self, url: &str, image_size: Option<(u32, u32)>) -> Option<String> {
+        let PaintData::Image {
+            scale_mode,
+            image_transform,
+            scaling_factor,
+            ..
+        } = self
+        else {
+            return None;
+        };
+
+        let (size, position, repeat) = match scale_mode {
+            ScaleMode::Fill => ("cover".to_string(), "center".to_string(), "no-repeat"),
+            ScaleMode::Fit => ("contain".to_string(), "center".to_string(), "no-repeat"),
+            ScaleMode::Tile => {
+                let size = match (image_size, scaling_factor) {
+                    (Some((width, height)), Some(factor)) => format!(
+                        "{} {}",
+                        px(width as f32 * factor),
+                        px(height as f32 * factor)
+                    ),
+                    _ => "auto".to_string(),
+                };
+                (size, "0 0".to_string(), "repeat")
+            }
+            ScaleMode::Stretch => match image_transform.map(crop) {
+                Some(Some((size, position))) => (size, position, "no-repeat"),
+                _ => ("100% 100%".to_string(), "0 0".to_string(), "no-repeat"),
+            },
+        };
+
+        Some(format!("url({url}) {position} / {size} {repeat}"))
+    }
+}
+
+/// Size and position of a cropped image. The transform maps the node to the visible part of the image,
+/// in coordinates relative to the image size.
+fn crop(transform: Transform) -> Option<(String, String)> {
+    let [[Some(sx), _, Some(tx)], [_, Some(sy), Some(ty)]] = transform else {
+        return None;
+    };
+
+    if sx <= 0.0 || sy <= 0.0 {
+        return None;
+    }
+
+    let position = |scale: f32, offset: f32| {
+        if (1.0 - scale).abs() < f32::EPSILON {
+            "0%".to_string()
+        } else {
+            format!("{}%", round(offset / (1.0 - scale) * 100.0))
+        }
+    };
+
+    Some((
+        format!("{}% {}%", round(100.0 / sx), round(100.0 / sy)),
+        format!("{} {}", position(sx, tx), position(sy, ty)),
+    ))
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -60,14 +173,204 @@ pub struct Paint {
     pub visible: bool,
     #[serde(default = "default_opacity")]
     pub opacity: f32,
-    // pub bound_variables: HashMap<string, VariableAlias | Vec<VariableAlias>>
+    #[serde(default, rename = "blendMode")]
+    pub blend_mode: BlendMode,
+    #[serde(default, rename = "boundVariables")]
+    pub bound_variables: HashMap<String, VariableAlias>,
     #[serde(flatten)]
     pub data: PaintData,
 }
 
+impl Paint {
+    /// Background layers of the paint, with its opacity folded into the colours alpha.
+    /// Images are only added when they are in `images`, the downloaded image fills by image ref,
+    /// and bound colours are the `var()` of their variable.
+    pub fn background_layers(
+        &self,
+        width: f32,
+        height: f32,
+        images: &HashMap<String, ImageFile>,
+        variables: &Variables,
+    ) -> Vec<String> {
+        if !self.visible {
+            return Vec::new();
+        }
+
+        match &self.data {
+            PaintData::Solid { color } => vec![self
+                .colour_variable(variables)
+                .unwrap_or_else(|| color.with_opacity(self.opacity).rgba())],
+            PaintData::Image { image_ref, .. } => image_ref
+                .as_ref()
+                .and_then(|r| images.get(r))
+                .and_then(|image| {
+                    let url = format!("../../{ASSETS_DIR}/{}", image.path);
+                    self.data.image_css(&url, image.size)
+                })
+                .into_iter()
+                .collect(),
+            data => data.gradient_css(width, height, self.opacity),
+        }
+    }
+
+    /// `var()` of the colour variable bound to a solid paint, mixed with transparent for its opacity
+    pub fn colour_variable(&self, variables: &Variables) -> Option<String> {
+        self.data.get_solid()?;
+        let colour = variables.var(self.bound_variables.get("color")?)?;
+
+        if self.opacity >= 1.0 {
+            Some(colour)
+        } else {
+            let percentage = round(self.opacity * 100.0);
+            Some(format!(
+                "color-mix(in srgb, {colour} {percentage}%, transparent)"
+            ))
+        }
+    }
+
+    /// A colour variable is bound to the paint
+    pub fn is_bound(&self) -> bool {
+        self.visible && self.bound_variables.contains_key("color")
+    }
+}
+
+/// Layered `background` and `background-blend-mode` values of fills stacked bottom to top, as Figma does.
+/// CSS lists the layers top to bottom and only the bottom one can be a colour.
+pub fn background(
+    fills: &[Paint],
+    width: f32,
+    height: f32,
+    images: &HashMap<String, ImageFile>,
+    variables: &Variables,
+) -> (String, String) {
+    let mut layers: Vec<String> = Vec::new();
+    let mut blend_modes: Vec<&str> = Vec::new();
+
+    for paint in fills {
+        for layer in paint.background_layers(width, height, images, variables) {
+            // Colours over other layers are drawn as a plain gradient
+            let layer = match paint.data.get_solid() {
+                Some(_) if !layers.is_empty() => format!("linear-gradient({layer}, {layer})"),
+                _ => layer,
+            };
+            layers.push(layer);
+            blend_modes.push(paint.blend_mode.css());
+        }
+    }
+
+    layers.reverse();
+    blend_modes.reverse();
+
+    let blend_mode = if blend_modes.iter().all(|b| *b == "normal") {
+        String::new()
+    } else {
+        blend_modes.join(", ")
+    };
+
+    (layers.join(", "), blend_mode)
+}
+
+/// `var()` of the colour variable bound to a single visible solid paint
+pub fn colour_variable(paints: &[Paint], variables: &Variables) -> Option<String> {
+    let mut visible = paints.iter().filter(|p| p.visible);
+
+    match (visible.next(), visible.next()) {
+        (Some(paint), None) => paint.colour_variable(variables),
+        _ => None,
+    }
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct PaintOverride {
     pub fills: Vec<Paint>,
     pub inherit_fil_style_id: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn image(json: &str) -> PaintData {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn image_css() {
+        let fill = image(r#"{ "type": "IMAGE", "scaleMode": "FILL", "imageRef": "abc" }"#);
+        assert_eq!(fill.get_image_ref().unwrap(), "abc");
+        assert_eq!(
+            fill.image_css("../../assets/images/abc.png", None).unwrap(),
+            "url(../../assets/images/abc.png) center / cover no-repeat"
+        );
+
+        let fit = image(r#"{ "type": "IMAGE", "scaleMode": "FIT", "imageRef": "abc" }"#);
+        assert_eq!(
+            fit.image_css("abc.png", None).unwrap(),
+            "url(abc.png) center / contain no-repeat"
+        );
+
+        let tile = image(
+            r#"{ "type": "IMAGE", "scaleMode": "TILE", "scalingFactor": 0.5, "imageRef": "abc" }"#,
+        );
+        assert_eq!(
+            tile.image_css("abc.png", Some((64, 30))).unwrap(),
+            "url(abc.png) 0 0 / 32px 15px repeat"
+        );
+        assert_eq!(
+            tile.image_css("abc.png", None).unwrap(),
+            "url(abc.png) 0 0 / auto repeat"
+        );
+
+        // Right half of the image
+        let stretch = image(
+            r#"{ "type": "IMAGE", "scaleMode": "STRETCH", "imageRef": "abc",
+                 "imageTransform": [[0.5, 0, 0.5], [0, 1, 0]] }"#,
+        );
+        assert_eq!(
+            stretch.image_css("abc.png", None).unwrap(),
+            "url(abc.png) 100% 0% / 200% 100% no-repeat"
+        );
+    }
+
+    #[test]
+    fn stacked_fills() {
+        let fills: Vec<Paint> = serde_json::from_str(
+            r#"[
+                { "type": "SOLID", "blendMode": "NORMAL", "color": { "r": 1, "g": 1, "b": 1, "a": 1 } },
+                { "type": "SOLID", "visible": false, "color": { "r": 0, "g": 1, "b": 0, "a": 1 } },
+                { "type": "IMAGE", "blendMode": "MULTIPLY", "scaleMode": "FILL", "imageRef": "abc" },
+                { "type": "SOLID", "opacity": 0.5, "blendMode": "NORMAL", "color": { "r": 1, "g": 0, "b": 0, "a": 1 } }
+            ]"#,
+        )
+        .unwrap();
+
+        let images = HashMap::from([(
+            "abc".to_string(),
+            ImageFile {
+                path: "images/abc.png".to_string(),
+                size: None,
+            },
+        )]);
+        let (layers, blend_modes) =
+            background(&fills, 100.0, 100.0, &images, &Variables::default());
+
+        assert_eq!(
+            layers,
+            "linear-gradient(rgba(255,0,0,0.5), rgba(255,0,0,0.5)), \
+             url(../../assets/images/abc.png) center / cover no-repeat, \
+             rgba(255,255,255,1)"
+        );
+        assert_eq!(blend_modes, "normal, multiply, normal");
+
+        // Without the downloaded image and blend modes
+        assert_eq!(
+            background(&fills, 100.0, 100.0, &HashMap::new(), &Variables::default()),
+            (
+                "linear-gradient(rgba(255,0,0,0.5), rgba(255,0,0,0.5)), rgba(255,255,255,1)"
+                    .to_string(),
+                String::new()
+            )
+        );
+    }
+}
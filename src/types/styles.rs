@@ -66,6 +66,37 @@ impl TypeStyle {
         }
     }
 
+    /// Font properties of the style, given as tokens and a utility class for the shared text styles
+    pub fn font_rules(&self) -> Vec<(&'static str, String)> {
+        let mut rules = vec![];
+
+        if !self.font_family.is_empty() {
+            rules.push(("font-family", self.font_family.to_string()));
+        }
+
+        if self.font_size != 0.0 {
+            rules.push(("font-size", format!("{:.0}px", self.font_size)));
+        }
+
+        if self.font_weight != 0.0 {
+            rules.push(("font-weight", format!("{:.0}", self.font_weight)));
+        }
+
+        if self.line_height() > 0.0 {
+            rules.push(("line-height", format!("{}", self.line_height())));
+        }
+
+        if self.letter_spacing != 0.0 {
+            rules.push(("letter-spacing", format!("{:.0}px", self.letter_spacing)));
+        }
+
+        if !self.text_transform().is_empty() {
+            rules.push(("text-transform", self.text_transform()));
+        }
+
+        rules
+    }
+
     pub fn text_align(&self) -> String {
         match self.text_align_horizontal {
             TextAlignHorizontal::Right => "right".to_string(),
@@ -4,4 +4,11 @@ pub struct Token {
     pub variable: String,
     pub value: String,
     pub theme: String,
+    /// CSS property of the value, for the tokens of a text style that are grouped in a utility class
+    pub property: Option<String>,
+}
+
+/// Key of the token of a style property, grid and text styles give several tokens
+pub fn token_id(style: &str, suffix: &str) -> String {
+    format!("{style}-{suffix}")
 }
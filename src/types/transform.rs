@@ -1,3 +1,175 @@
+use super::rectangle::Rectangle;
+use super::vector::Vector;
+use crate::utils::round;
+
 //  [[a, b, c], [d, e, f]]
 //#[derive (Deserialize, Serialize, Debug, Clone)]
 pub type Transform = [[Option<f32>; 3]; 2];
+
+/// Affine transform split into its translation and the CSS transform functions,
+/// applied as `rotate() skewX() scale()`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Decomposed {
+    pub translate_x: f32,
+    pub translate_y: f32,
+    /// Degrees, clockwise like CSS
+    pub rotation: f32,
+    /// Degrees
+    pub skew_x: f32,
+    pub scale_x: f32,
+    pub scale_y: f32,
+}
+
+/// Splits the `relative_transform` of a node, the x axis of the node gives the rotation and the
+/// horizontal scale, what remains of the y axis gives the skew and the vertical scale (negative when flipped).
+pub fn decompose(transform: &Transform) -> Decomposed {
+    let value = |row: usize, column: usize, default: f32| transform[row][column].unwrap_or(default);
+    let (a, c, tx) = (value(0, 0, 1.0), value(0, 1, 0.0), value(0, 2, 0.0));
+    let (b, d, ty) = (value(1, 0, 0.0), value(1, 1, 1.0), value(1, 2, 0.0));
+
+    let scale_x = a.hypot(b);
+    if scale_x == 0.0 {
+        return Decomposed {
+            translate_x: tx,
+            translate_y: ty,
+            rotation: 0.0,
+            skew_x: 0.0,
+            scale_x: 0.0,
+            scale_y: d,
+        };
+    }
+
+    let angle = b.atan2(a);
+    let (sin, cos) = angle.sin_cos();
+    let shear = c * cos + d * sin;
+    let scale_y = d * cos - c * sin;
+    let skew_x = if scale_y == 0.0 {
+        0.0
+    } else {
+        (shear / scale_y).atan().to_degrees()
+    };
+
+    Decomposed {
+        translate_x: tx,
+        translate_y: ty,
+        rotation: angle.to_degrees(),
+        skew_x,
+        scale_x,
+        scale_y,
+    }
+}
+
+impl Decomposed {
+    /// Only rotated, skewed or scaled nodes need a CSS transform, the translation is the position of the node.
+    pub fn is_identity(&self) -> bool {
+        round(self.rotation) == 0.0
+            && round(self.skew_x) == 0.0
+            && round(self.scale_x) == 1.0
+            && round(self.scale_y) == 1.0
+    }
+
+    /// `transform` value without the translation, to apply around the center of the node
+    pub fn css(&self) -> String {
+        let mut functions = vec![];
+
+        if round(self.rotation) != 0.0 {
+            functions.push(format!("rotate({}deg)", round(self.rotation)));
+        }
+
+        if round(self.skew_x) != 0.0 {
+            functions.push(format!("skewX({}deg)", round(self.skew_x)));
+        }
+
+        if round(self.scale_x) != 1.0 || round(self.scale_y) != 1.0 {
+            if round(self.scale_x) == round(self.scale_y) {
+                functions.push(format!("scale({})", round(self.scale_x)));
+            } else {
+                functions.push(format!(
+                    "scale({}, {})",
+                    round(self.scale_x),
+                    round(self.scale_y)
+                ));
+            }
+        }
+
+        functions.join(" ")
+    }
+
+    /// Untransformed box of the node: the transform maps the center of the node to the
+    /// center of its bounding box, so the box of `size` is centered on the bounding box.
+    pub fn untransformed_bounds(&self, bounds: &Rectangle, size: &Vector) -> Rectangle {
+        let (width, height) = (size.x(), size.y());
+
+        Rectangle {
+            x: bounds
+                .x
+                .map(|x| x + (bounds.width.unwrap_or(width) - width) / 2.0),
+            y: bounds
+                .y
+                .map(|y| y + (bounds.height.unwrap_or(height) - height) / 2.0),
+            width: Some(width),
+            height: Some(height),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decompose_transforms() {
+        let translation = [
+            [Some(1.0), Some(0.0), Some(10.0)],
+            [Some(0.0), Some(1.0), Some(20.0)],
+        ];
+        let decomposed = decompose(&translation);
+        assert!(decomposed.is_identity());
+        assert_eq!(
+            (decomposed.translate_x, decomposed.translate_y),
+            (10.0, 20.0)
+        );
+        assert_eq!(decomposed.css(), "");
+
+        // Rotated 90deg counterclockwise in Figma
+        let rotation = [
+            [Some(0.0), Some(1.0), Some(0.0)],
+            [Some(-1.0), Some(0.0), Some(40.0)],
+        ];
+        assert_eq!(decompose(&rotation).css(), "rotate(-90deg)");
+
+        // Flipped horizontally
+        let flip = [
+            [Some(-1.0), Some(0.0), Some(40.0)],
+            [Some(0.0), Some(1.0), Some(0.0)],
+        ];
+        assert_eq!(decompose(&flip).css(), "rotate(180deg) scale(1, -1)");
+
+        let skew = [
+            [Some(2.0), Some(1.0), Some(0.0)],
+            [Some(0.0), Some(1.0), Some(0.0)],
+        ];
+        assert_eq!(decompose(&skew).css(), "skewX(45deg) scale(2, 1)");
+
+        let decomposed = decompose(&rotation);
+        let bounds = Rectangle {
+            x: Some(0.0),
+            y: Some(0.0),
+            width: Some(20.0),
+            height: Some(40.0),
+        };
+        let size = Vector {
+            x: Some(40.0),
+            y: Some(20.0),
+        };
+        assert_eq!(
+            decomposed.untransformed_bounds(&bounds, &size),
+            Rectangle {
+                x: Some(-10.0),
+                y: Some(10.0),
+                width: Some(40.0),
+                height: Some(20.0),
+            }
+        );
+    }
+}
@@ -0,0 +1,248 @@
+//! Figma variables, from the `/v1/files/:key/variables/local` response or the same JSON exported locally
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use super::colour::Colour;
+use crate::cli::Naming;
+use crate::utils::{custom_property, parse_name, round};
+
+/// Response of `/v1/files/:key/variables/local`
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalVariables {
+    #[serde(default)]
+    pub error: bool,
+    #[serde(default)]
+    pub meta: Variables,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Variables {
+    #[serde(default)]
+    pub variables: HashMap<String, Variable>,
+    #[serde(default)]
+    pub variable_collections: HashMap<String, VariableCollection>,
+    /// Names of the custom properties, from the project config
+    #[serde(skip)]
+    pub naming: Naming,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Variable {
+    pub id: String,
+    pub name: String,
+    pub variable_collection_id: String,
+    pub resolved_type: VariableType,
+    #[serde(default)]
+    pub values_by_mode: HashMap<String, VariableValue>,
+    /// Properties the variable can be bound to, ex: `GAP` or `OPACITY`
+    #[serde(default)]
+    pub scopes: Vec<String>,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub remote: bool,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum VariableType {
+    Boolean,
+    Float,
+    String,
+    Color,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum VariableValue {
+    Alias(VariableAlias),
+    Colour(Colour),
+    Number(f32),
+    Boolean(bool),
+    Text(String),
+}
+
+/// Reference to a variable, in the values of other variables and in the `boundVariables` of the nodes
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct VariableAlias {
+    #[serde(rename = "type")]
+    pub alias_type: String,
+    pub id: String,
+}
+
+/// `boundVariables` of a node: a single alias, or a list for the fills, strokes and text ranges
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum BoundVariable {
+    Alias(VariableAlias),
+    List(Vec<VariableAlias>),
+    Other(serde_json::Value),
+}
+
+impl BoundVariable {
+    /// The alias, when a single variable is bound to the property
+    pub fn alias(&self) -> Option<&VariableAlias> {
+        match self {
+            BoundVariable::Alias(alias) => Some(alias),
+            BoundVariable::List(list) if list.len() == 1 => list.first(),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct VariableCollection {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub modes: Vec<VariableMode>,
+    pub default_mode_id: String,
+    #[serde(default)]
+    pub variable_ids: Vec<String>,
+    #[serde(default)]
+    pub remote: bool,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct VariableMode {
+    pub mode_id: String,
+    pub name: String,
+}
+
+// Figma scopes of the float variables that are not lengths
+const UNITLESS_SCOPES: [&str; 3] = ["FONT_WEIGHT", "TEXT_CONTENT", "FONT_VARIATIONS"];
+
+impl Variables {
+    /// CSS custom property of the variable, ex: `color/primary` is `--color-primary`
+    pub fn variable(&self, id: &str) -> Option<String> {
+        self.variables
+            .get(id)
+            .map(|v| custom_property(&v.name, &self.naming))
+    }
+
+    /// `var()` reference to the aliased variable
+    pub fn var(&self, alias: &VariableAlias) -> Option<String> {
+        self.variable(&alias.id).map(|v| format!("var({v})"))
+    }
+
+    /// Custom properties by theme: the default mode of each collection is `:root`,
+    /// the other modes are theme classes like the styles, ex: the `Dark` mode is `.theme-dark`
+    pub fn css(&self) -> BTreeMap<String, Vec<String>> {
+        let mut themes: BTreeMap<String, Vec<String>> = BTreeMap::new();
+
+        for variable in self.variables.values() {
+            let Some(collection) = self
+                .variable_collections
+                .get(&variable.variable_collection_id)
+            else {
+                continue;
+            };
+
+            for mode in collection.modes.iter() {
+                let Some(value) = variable.values_by_mode.get(&mode.mode_id) else {
+                    continue;
+                };
+                let Some(value) = self.value_css(variable, value) else {
+                    continue;
+                };
+
+                let theme = if mode.mode_id == collection.default_mode_id {
+                    ":root".to_string()
+                } else {
+                    mode_theme(&mode.name)
+                };
+                themes.entry(theme).or_default().push(format!(
+                    "{}: {value};",
+                    custom_property(&variable.name, &self.naming)
+                ));
+            }
+        }
+
+        for rules in themes.values_mut() {
+            rules.sort_by_key(|a| a.to_lowercase());
+        }
+
+        themes
+    }
+
+    fn value_css(&self, variable: &Variable, value: &VariableValue) -> Option<String> {
+        match value {
+            VariableValue::Alias(alias) => self.var(alias),
+            VariableValue::Colour(colour) => Some(colour.rgba()),
+            VariableValue::Number(number) => Some(number_css(*number, &variable.scopes)),
+            VariableValue::Text(text) => Some(format!("\"{}\"", text.replace('"', "\\\""))),
+            // CSS has no booleans
+            VariableValue::Boolean(_) => None,
+        }
+    }
+}
+
+/// Numbers are lengths in Figma, apart from the opacity (a percentage) and the font weight
+fn number_css(number: f32, scopes: &[String]) -> String {
+    let number = round(number);
+
+    if !scopes.is_empty() && scopes.iter().all(|s| s == "OPACITY") {
+        return format!("{number}%");
+    }
+
+    if scopes.is_empty() || scopes.iter().all(|s| UNITLESS_SCOPES.contains(&s.as_str())) {
+        format!("{number}")
+    } else {
+        format!("{number}px")
+    }
+}
+
+/// Modes are themes like the `theme` group of the style names
+fn mode_theme(mode: &str) -> String {
+    let name = parse_name(&mode.to_string());
+
+    if name.contains("theme") {
+        name
+    } else {
+        format!("theme-{name}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::VARIABLES_FIXTURE;
+
+    #[test]
+    fn modes_css() {
+        let variables = serde_json::from_str::<LocalVariables>(VARIABLES_FIXTURE)
+            .unwrap()
+            .meta;
+
+        let css = variables.css();
+        assert_eq!(
+            css[":root"],
+            vec![
+                "--brand-blue: rgba(51,102,255,1);",
+                "--button-background: var(--brand-blue);",
+                "--spacing-medium: 16px;",
+            ]
+        );
+        assert_eq!(
+            css["theme-dark"],
+            vec![
+                "--brand-blue: rgba(0,0,128,1);",
+                "--button-background: var(--brand-blue);",
+            ]
+        );
+    }
+
+    #[test]
+    fn numbers() {
+        assert_eq!(number_css(50.0, &["OPACITY".to_string()]), "50%");
+        assert_eq!(number_css(600.0, &["FONT_WEIGHT".to_string()]), "600");
+        assert_eq!(number_css(8.0, &["ALL_SCOPES".to_string()]), "8px");
+    }
+}
@@ -3,18 +3,21 @@ use super::{
     easing_type::EasingType,
     effect::{Effect, EffectType},
     export_settings::ExportSetting,
-    layout::{LayoutConstraint, LayoutSizingMode},
+    frame::Frame,
+    layout::{LayoutConstraint, LayoutPositioning, LayoutSizingMode},
     node_common::NodeCommon,
-    paint::Paint,
+    paint::{self, Paint},
     path::Path,
     rectangle::Rectangle,
     stroke_align::StrokeAlign,
     styles::TypeStyle,
     text::TextTruncation,
+    token::{token_id, Token},
     transform::Transform,
+    variables::{BoundVariable, Variables},
     vector::Vector,
 };
-use crate::utils::{default_opacity, parse_name};
+use crate::utils::{css_opacity, default_opacity, parse_name, px};
 use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, HashMap};
 
@@ -28,6 +31,7 @@ pub struct VectorCommon {
     pub blend_mode: BlendMode,
     pub preserve_ratio: Option<bool>,
     pub constraints: LayoutConstraint,
+    pub layout_positioning: Option<LayoutPositioning>, // AUTO ABSOLUTE
     pub transition_node_id: Option<String>,
     pub transition_duration: Option<f32>,
     pub transition_easing: Option<EasingType>,
@@ -57,6 +61,8 @@ pub struct VectorCommon {
     pub stroke_miter_angle: Option<f32>,
     pub stroke_geometry: Option<Vec<Path>>,
     pub styles: Option<HashMap<String, String>>,
+    #[serde(default)]
+    pub bound_variables: HashMap<String, BoundVariable>,
 }
 
 impl VectorCommon {
@@ -64,20 +70,90 @@ impl VectorCommon {
         parse_name(&self.node.name.to_string())
     }
 
-    pub fn text_colour(&self) -> String {
-        for paint in self.fills.iter() {
-            if paint.visible && paint.data.get_solid().is_some() {
-                // TODO: get colours, maybe move this logic to get_solid
-                // TODO: build string for when there's multiple backgrounds
-                // Multiple Solid backgrounds converts to a linear gradient, for now we select the first one passing the condition.
-                return match paint.data.get_solid() {
-                    Some(c) => c.rgba(),
-                    None => String::new(),
-                };
+    /// Offsets of the node when the parent positions its children, see `Frame::positions_child`
+    pub fn position(&self, parent: &Frame) -> BTreeMap<String, String> {
+        if parent.is_grid() {
+            return parent.grid_area(self.absolute_bounding_box.as_ref());
+        }
+
+        if !parent.positions_child(self.layout_positioning.as_ref()) {
+            return BTreeMap::new();
+        }
+
+        match (&self.absolute_bounding_box, &parent.absolute_bounding_box) {
+            (Some(bounds), Some(parent_bounds)) => {
+                self.constraints
+                    .position(bounds, self.relative_transform.as_ref(), parent_bounds)
             }
+            _ => BTreeMap::new(),
         }
+    }
 
-        return String::new();
+    /// Replaces the text values bound to Figma variables by `var()` references, for the properties in `rules`
+    pub fn bind_variables(&self, rules: &mut BTreeMap<String, String>, variables: &Variables) {
+        for (field, property) in [
+            ("fontFamily", "font-family"),
+            ("fontSize", "font-size"),
+            ("fontWeight", "font-weight"),
+            ("lineHeight", "line-height"),
+            ("letterSpacing", "letter-spacing"),
+            ("opacity", "opacity"),
+        ] {
+            let value = self
+                .bound_variables
+                .get(field)
+                .and_then(BoundVariable::alias)
+                .and_then(|alias| variables.var(alias));
+
+            if let (Some(rule), Some(value)) = (rules.get_mut(property), value) {
+                *rule = value;
+            }
+        }
+
+        if rules.get("color").is_some_and(|c| c != "transparent") {
+            if let Some(colour) = paint::colour_variable(&self.fills, variables) {
+                rules.insert("color".to_string(), colour);
+            }
+        }
+
+        if rules.contains_key("background") && self.fills.iter().any(Paint::is_bound) {
+            rules.insert("background".to_string(), self.background_layers(variables));
+        }
+    }
+
+    /// Colour of a text with a single solid fill
+    pub fn text_colour(&self) -> String {
+        let mut fills = self.fills.iter().filter(|p| p.visible);
+
+        match (fills.next(), fills.next()) {
+            (Some(paint), None) => match paint.data.get_solid() {
+                Some(c) => c.with_opacity(paint.opacity).rgba(),
+                None => String::new(),
+            },
+            _ => String::new(),
+        }
+    }
+
+    /// Stacked fills and gradients of a text, drawn as a background clipped to the glyphs
+    pub fn text_background(&self) -> String {
+        self.background_layers(&Variables::default())
+    }
+
+    fn background_layers(&self, variables: &Variables) -> String {
+        if !self.text_colour().is_empty() {
+            return String::new();
+        }
+
+        let (width, height) = match self.absolute_bounding_box {
+            Some(Rectangle {
+                width: Some(width),
+                height: Some(height),
+                ..
+            }) if width > 0.0 && height > 0.0 => (width, height),
+            _ => (1.0, 1.0),
+        };
+
+        paint::background(&self.fills, width, height, &HashMap::new(), variables).0
     }
 
     pub fn border_colour(&self) -> String {
@@ -100,8 +176,10 @@ impl VectorCommon {
             .iter()
             .filter(|x| x.visible)
             .map(|e| match e.effect_type {
-                EffectType::InnerShadow => format!("inset {}", VectorCommon::shadow(e)),
-                EffectType::DropShadow => VectorCommon::shadow(e),
+                EffectType::InnerShadow => {
+                    format!("inset {}", e.shadow(None, &Variables::default()))
+                }
+                EffectType::DropShadow => e.shadow(None, &Variables::default()),
                 _ => String::new(),
             })
             .collect();
@@ -109,24 +187,10 @@ impl VectorCommon {
         effect_list.join(", ")
     }
 
-    fn shadow(effect: &Effect) -> String {
-        let Effect {
-            offset,
-            spread,
-            radius,
-            color,
-            ..
-        } = effect;
-        let x = offset.x();
-        let y = offset.y();
-        let rgba = color.rgba();
-        format!("{x:0}px {y:0}px {radius:0}px {spread:0}px {rgba}")
-    }
-
     pub fn width(&self) -> String {
         match self.absolute_bounding_box {
             Some(rec) => match rec.width {
-                Some(w) => format!("{}px", w),
+                Some(w) => px(w),
                 None => String::new(),
             },
             None => String::new(),
@@ -136,7 +200,7 @@ impl VectorCommon {
     pub fn height(&self) -> String {
         match self.absolute_bounding_box {
             Some(rec) => match rec.height {
-                Some(h) => format!("{}px", h),
+                Some(h) => px(h),
                 None => String::new(),
             },
             None => String::new(),
@@ -147,19 +211,19 @@ impl VectorCommon {
         let mut styles: BTreeMap<String, String> = BTreeMap::new();
 
         if let Some(x) = self.min_width {
-            styles.insert("min-width".to_string(), format!("{}px", x));
+            styles.insert("min-width".to_string(), px(x));
         }
 
         if let Some(x) = self.max_width {
-            styles.insert("max-width".to_string(), format!("{}px", x));
+            styles.insert("max-width".to_string(), px(x));
         }
 
         if let Some(x) = self.min_height {
-            styles.insert("min-height".to_string(), format!("{}px", x));
+            styles.insert("min-height".to_string(), px(x));
         }
 
         if let Some(x) = self.max_height {
-            styles.insert("max-height".to_string(), format!("{}px", x));
+            styles.insert("max-height".to_string(), px(x));
         }
 
         if self.layout_sizing_horizontal.is_fixed() {
@@ -187,40 +251,41 @@ impl VectorCommon {
         styles
     }
 
-    pub fn css(&self, style: &TypeStyle) -> BTreeMap<String, String> {
+    pub fn css(
+        &self,
+        style: &TypeStyle,
+        tokens: &HashMap<String, Token>,
+    ) -> BTreeMap<String, String> {
         let mut rules: BTreeMap<String, String> = BTreeMap::new();
 
-        if !self.text_colour().is_empty() {
+        if !self.text_background().is_empty() {
+            rules.insert("background".to_string(), self.text_background());
+            rules.insert("background-clip".to_string(), "text".to_string());
+            rules.insert("-webkit-background-clip".to_string(), "text".to_string());
+            rules.insert("color".to_string(), "transparent".to_string());
+        } else if !self.text_colour().is_empty() {
             rules.insert("color".to_string(), self.text_colour());
         }
 
-        if !style.font_family.is_empty() {
-            rules.insert("font-family".to_string(), style.font_family.to_string());
+        if !css_opacity(self.opacity).is_empty() {
+            rules.insert("opacity".to_string(), css_opacity(self.opacity));
         }
 
-        if style.font_size != 0.0 {
-            rules.insert("font-size".to_string(), format!("{:.0}px", style.font_size));
-        }
-
-        if style.font_weight != 0.0 {
-            rules.insert(
-                "font-weight".to_string(),
-                format!("{:.0}", style.font_weight),
-            );
-        }
-
-        if style.line_height() > 0.0 {
+        if self.blend_mode.css() != "normal" {
             rules.insert(
-                "line-height".to_string(),
-                format!("{}", style.line_height()),
+                "mix-blend-mode".to_string(),
+                self.blend_mode.css().to_string(),
             );
         }
 
-        if style.letter_spacing != 0.0 {
-            rules.insert(
-                "letter-spacing".to_string(),
-                format!("{:.0}px", style.letter_spacing),
-            );
+        // Text styles are given as tokens, see `generate_text_tokens`
+        let text_style = self.styles.as_ref().and_then(|s| s.get("text"));
+        for (property, value) in style.font_rules() {
+            let value = match text_style.and_then(|id| tokens.get(&token_id(id, property))) {
+                Some(token) => format!("var({})", token.variable),
+                None => value,
+            };
+            rules.insert(property.to_string(), value);
         }
 
         if !self.sizes().is_empty() {
@@ -240,13 +305,6 @@ impl VectorCommon {
             );
         }
 
-        if !style.text_transform().is_empty() {
-            rules.insert(
-                "text-transform".to_string(),
-                format!("{}", style.text_transform()),
-            );
-        }
-
         if !style.font_variant().is_empty() {
             rules.insert(
                 "font-variant".to_string(),
@@ -0,0 +1,338 @@
+//! Nodes with export settings, rendered through the `/v1/images/:key` endpoint into the `assets/` folder,
+//! and the images of the image fills, saved into `assets/images/`
+
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    path::Path,
+};
+
+use crate::types::{export_format::ExportFormat, node::Node};
+
+pub const ASSETS_DIR: &str = "assets";
+pub const IMAGES_DIR: &str = "images";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Asset {
+    pub node_id: String,
+    /// File name in the `assets/` folder: `{node-name}{suffix}.{format}`
+    pub file_name: String,
+    pub format: ExportFormat,
+    pub scale: f32,
+    /// Slices only mark an area of the design to export, they are not part of the markup
+    pub is_slice: bool,
+}
+
+/// Every export setting of the document, in tree order.
+pub fn collect_assets(document: &Node) -> Vec<Asset> {
+    let mut assets: Vec<Asset> = Vec::new();
+    let mut names: HashSet<String> = HashSet::new();
+
+    collect(document, &mut assets, &mut names);
+
+    assets
+}
+
+fn collect(node: &Node, assets: &mut Vec<Asset>, names: &mut HashSet<String>) {
+    let settings = node.export_settings();
+
+    if !settings.is_empty() {
+        let common = node.common();
+        let mut name = common.get_name();
+        if name.is_empty() {
+            name = common.id.replace(':', "-");
+        }
+
+        // Nodes with the same name get a number, so their files don't overwrite each other
+        let mut base = name.clone();
+        let mut count = 1;
+        while !names.insert(base.clone()) {
+            count += 1;
+            base = format!("{name}-{count}");
+        }
+
+        for setting in settings {
+            assets.push(Asset {
+                node_id: common.id.clone(),
+                file_name: format!(
+                    "{base}{}.{}",
+                    file_suffix(&setting.suffix),
+                    setting.format.extension()
+                ),
+                format: setting.format,
+                scale: setting.scale(node.bounding_box()),
+                is_slice: matches!(node, Node::SLICE { .. }),
+            });
+        }
+    }
+
+    for child in node.common().children.iter() {
+        collect(child, assets, names);
+    }
+}
+
+/// Suffix of the export setting in the file name, without the characters that could leave the
+/// `assets/` folder, ex: `/../@2x` is `@2x`
+fn file_suffix(suffix: &str) -> String {
+    suffix
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || matches!(c, '@' | '-' | '_'))
+        .collect()
+}
+
+/// Image used in the markup of each exported node, relative to the `assets/` folder.
+/// Vectors are preferred, otherwise the first export setting is used.
+pub fn asset_paths(assets: &[Asset]) -> HashMap<String, String> {
+    let mut paths: HashMap<String, String> = HashMap::new();
+
+    for asset in assets.iter().filter(|a| !a.is_slice) {
+        match paths.get(&asset.node_id) {
+            Some(path) if path.ends_with(".svg") || asset.format != ExportFormat::Svg => {}
+            _ => {
+                paths.insert(asset.node_id.clone(), asset.file_name.clone());
+            }
+        }
+    }
+
+    paths
+}
+
+/// Image refs of the visible image fills of the frames.
+pub fn collect_image_refs(node: &Node) -> BTreeSet<String> {
+    let mut refs: BTreeSet<String> = BTreeSet::new();
+    collect_refs(node, &mut refs);
+    refs
+}
+
+fn collect_refs(node: &Node, refs: &mut BTreeSet<String>) {
+    if let Some(frame) = node.is_frame() {
+        for paint in frame.fills.iter().filter(|p| p.visible) {
+            if let Some(image_ref) = paint.data.get_image_ref() {
+                refs.insert(image_ref.clone());
+            }
+        }
+    }
+
+    for child in node.common().children.iter() {
+        collect_refs(child, refs);
+    }
+}
+
+/// Image fill saved into `assets/images/`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImageFile {
+    /// Path relative to the `assets/` folder
+    pub path: String,
+    /// Width and height of the image in pixels, when its format is known
+    pub size: Option<(u32, u32)>,
+}
+
+/// Image fills already saved into `{assets_dir}/images/`, by image ref, relative to `assets_dir`.
+/// Lets the css be generated offline from the images downloaded by a previous run.
+pub fn image_paths(assets_dir: &Path) -> HashMap<String, ImageFile> {
+    let Ok(entries) = std::fs::read_dir(assets_dir.join(IMAGES_DIR)) else {
+        return HashMap::new();
+    };
+
+    entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let path = entry.path();
+            let image_ref = path.file_stem()?.to_str()?.to_string();
+            let file_name = path.file_name()?.to_str()?.to_string();
+            let image = ImageFile {
+                path: format!("{IMAGES_DIR}/{file_name}"),
+                size: std::fs::read(&path).ok().and_then(|c| image_size(&c)),
+            };
+            Some((image_ref, image))
+        })
+        .collect()
+}
+
+/// Image fills are served without extension, it's guessed from the content.
+pub fn image_extension(content: &[u8]) -> &'static str {
+    match content {
+        [0x89, b'P', b'N', b'G', ..] => "png",
+        [0xFF, 0xD8, 0xFF, ..] => "jpg",
+        [b'G', b'I', b'F', b'8', ..] => "gif",
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => "webp",
+        _ => "png",
+    }
+}
+
+/// Width and height of a PNG, GIF, JPEG or WebP image, from its header
+pub fn image_size(content: &[u8]) -> Option<(u32, u32)> {
+    let be = |at: usize| -> Option<u32> {
+        Some(u16::from_be_bytes(content.get(at..at + 2)?.try_into().ok()?) as u32)
+    };
+    let le = |at: usize, len: usize| -> Option<u32> {
+        let bytes = content.get(at..at + len)?;
+        Some(bytes.iter().rev().fold(0, |n, b| (n << 8) | *b as u32))
+    };
+
+    match image_extension(content) {
+        "png" if content.starts_with(b"\x89PNG") => Some((
+            u32::from_be_bytes(content.get(16..20)?.try_into().ok()?),
+            u32::from_be_bytes(content.get(20..24)?.try_into().ok()?),
+        )),
+        "gif" => Some((le(6, 2)?, le(8, 2)?)),
+        "webp" => match content.get(12..16)? {
+            b"VP8X" => Some((le(24, 3)? + 1, le(27, 3)? + 1)),
+            b"VP8 " => Some((le(26, 2)? & 0x3FFF, le(28, 2)? & 0x3FFF)),
+            b"VP8L" => {
+                let bits = le(21, 4)?;
+                Some(((bits & 0x3FFF) + 1, ((bits >> 14) & 0x3FFF) + 1))
+            }
+            _ => None,
+        },
+        "jpg" => {
+            // The size is in the start of frame segment
+            let mut at = 2;
+            loop {
+                if *content.get(at)? != 0xFF {
+                    return None;
+                }
+                let marker = *content.get(at + 1)?;
+                if (0xC0..=0xCF).contains(&marker) && ![0xC4, 0xC8, 0xCC].contains(&marker) {
+                    return Some((be(at + 7)?, be(at + 5)?));
+                }
+                at += 2 + be(at + 2)? as usize;
+            }
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test::FILE_FIXTURE, types::file::FigmaData};
+
+    #[test]
+    fn graphics() {
+        let file: FigmaData = serde_json::from_str(FILE_FIXTURE).unwrap();
+        let node = |id: &str| find(&file.document, id).unwrap().clone();
+
+        // The card has a title, the icon frame is only made of the star
+        let card = node("2:1");
+        let star = node("2:5");
+        let mut icon = card.clone();
+        icon.common_mut().children = vec![star.clone()];
+
+        assert!(!card.is_graphic());
+        assert!(star.is_graphic());
+        assert!(icon.is_graphic());
+    }
+
+    fn find<'a>(node: &'a Node, id: &str) -> Option<&'a Node> {
+        if node.common().id == id {
+            return Some(node);
+        }
+
+        node.common()
+            .children
+            .iter()
+            .find_map(|child| find(child, id))
+    }
+
+    #[test]
+    fn collect_exports() {
+        let document: Node = serde_json::from_str(
+            r#"{ "type": "DOCUMENT", "id": "0:0", "name": "Document", "children": [
+                { "type": "CANVAS", "id": "0:1", "name": "Icons", "children": [
+                    { "type": "SLICE", "id": "1:1", "name": "Hero",
+                      "absoluteBoundingBox": { "x": 0, "y": 0, "width": 200, "height": 100 },
+                      "exportSettings": [
+                        { "suffix": "", "format": "PNG", "constraint": { "type": "WIDTH", "value": 400 } },
+                        { "suffix": "/../../@1x", "format": "PNG", "constraint": { "type": "SCALE", "value": 1 } }
+                      ] },
+                    { "type": "SLICE", "id": "1:2", "name": "Icon/Close",
+                      "absoluteBoundingBox": { "x": 0, "y": 0, "width": 24, "height": 24 },
+                      "exportSettings": [
+                        { "suffix": "@2x", "format": "PNG", "constraint": { "type": "SCALE", "value": 2 } },
+                        { "suffix": "", "format": "SVG", "constraint": { "type": "SCALE", "value": 2 } }
+                      ] },
+                    { "type": "SLICE", "id": "1:3", "name": "Icon Close",
+                      "absoluteBoundingBox": { "x": 0, "y": 0, "width": 24, "height": 24 },
+                      "exportSettings": [
+                        { "suffix": "", "format": "JPG", "constraint": { "type": "HEIGHT", "value": 12 } }
+                      ] }
+                ]}
+            ]}"#,
+        )
+        .unwrap();
+
+        let assets = collect_assets(&document);
+        let files: Vec<(&str, f32)> = assets
+            .iter()
+            .map(|a| (a.file_name.as_str(), a.scale))
+            .collect();
+
+        assert_eq!(
+            files,
+            vec![
+                ("hero.png", 2.0),
+                // The suffix can't leave the assets folder
+                ("hero@1x.png", 1.0),
+                ("icon-close@2x.png", 2.0),
+                ("icon-close.svg", 1.0),
+                ("icon-close-2.jpg", 0.5),
+            ]
+        );
+        // Slices are not referenced from the markup
+        assert!(asset_paths(&assets).is_empty());
+
+        let nodes: Vec<Asset> = assets
+            .into_iter()
+            .map(|a| Asset {
+                is_slice: false,
+                ..a
+            })
+            .collect();
+        let paths = asset_paths(&nodes);
+        assert_eq!(paths["1:1"], "hero.png");
+        assert_eq!(paths["1:2"], "icon-close.svg");
+        assert_eq!(paths["1:3"], "icon-close-2.jpg");
+    }
+
+    #[test]
+    fn images() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(image_paths(dir.path()).is_empty());
+
+        let content = b"\x89PNG\r\n";
+        let images_dir = dir.path().join(IMAGES_DIR);
+        std::fs::create_dir_all(&images_dir).unwrap();
+        std::fs::write(
+            images_dir.join(format!("abc.{}", image_extension(content))),
+            content,
+        )
+        .unwrap();
+
+        assert_eq!(
+            image_paths(dir.path())["abc"],
+            ImageFile {
+                path: "images/abc.png".to_string(),
+                size: None,
+            }
+        );
+        assert_eq!(image_extension(b"\xFF\xD8\xFF\xE0"), "jpg");
+    }
+
+    #[test]
+    fn sizes() {
+        let png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR\0\0\x01\x2c\0\0\0\xc8";
+        assert_eq!(image_size(png), Some((300, 200)));
+
+        let gif = b"GIF89a\x2c\x01\xc8\x00";
+        assert_eq!(image_size(gif), Some((300, 200)));
+
+        // APP0 segment before the start of frame
+        let jpg = b"\xFF\xD8\xFF\xE0\0\x04\0\0\xFF\xC0\0\x11\x08\0\xc8\x01\x2c";
+        assert_eq!(image_size(jpg), Some((300, 200)));
+
+        let webp = b"RIFF\0\0\0\0WEBPVP8X\0\0\0\0\0\0\0\0\x2b\x01\0\xc7\0\0";
+        assert_eq!(image_size(webp), Some((300, 200)));
+
+        assert_eq!(image_size(b"\x89PNG\r\n"), None);
+    }
+}
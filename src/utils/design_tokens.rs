@@ -0,0 +1,325 @@
+//! Design tokens of the Figma styles in the W3C Design Tokens Community Group format
+//! (https://design-tokens.github.io/community-group/format/), grouped by the `/` hierarchy of the style names
+
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+};
+
+use serde_json::{json, Map, Value};
+
+use crate::{
+    types::{
+        effect::{Effect, EffectType},
+        file::FigmaData,
+        layout::LayoutGrid,
+        node::Node,
+        paint::Paint,
+        styles::{Style, TypeStyle},
+    },
+    utils::{parse_name, px, round},
+};
+
+pub const DESIGN_TOKENS_FILE: &str = "tokens.json";
+
+/// Tokens of the styles used in the document. There are no `duration` tokens, the transition
+/// durations of the prototypes are set on the nodes and have no style name.
+pub fn design_tokens(file: &FigmaData) -> Value {
+    let mut tokens = Map::new();
+    let mut seen = HashSet::new();
+
+    collect(&file.document, &file.styles, &mut tokens, &mut seen);
+
+    Value::Object(tokens)
+}
+
+/// Writes `tokens.json` into the output directory
+pub fn write_design_tokens(file: &FigmaData, out_dir: &Path) {
+    let tokens = design_tokens(file);
+
+    if tokens.as_object().is_some_and(|t| !t.is_empty()) {
+        let _ = std::fs::create_dir_all(out_dir);
+        let _ = std::fs::write(
+            out_dir.join(DESIGN_TOKENS_FILE),
+            serde_json::to_string_pretty(&tokens).unwrap(),
+        );
+    }
+}
+
+fn collect(
+    node: &Node,
+    styles: &HashMap<String, Style>,
+    tokens: &mut Map<String, Value>,
+    seen: &mut HashSet<String>,
+) {
+    let (node_styles, fills, strokes, effects) = match (node.is_frame(), node.is_vector()) {
+        (Some(frame), _) => (
+            frame.styles.as_ref(),
+            &frame.fills,
+            &frame.strokes,
+            &frame.effects,
+        ),
+        (None, Some(vector)) => (
+            vector.styles.as_ref(),
+            &vector.fills,
+            &vector.strokes,
+            &vector.effects,
+        ),
+        _ => {
+            for child in node.common().children.iter() {
+                collect(child, styles, tokens, seen);
+            }
+            return;
+        }
+    };
+
+    for (key, id) in node_styles.into_iter().flatten() {
+        let Some(style) = styles.get(id) else {
+            continue;
+        };
+        if !seen.insert(id.clone()) {
+            continue;
+        }
+
+        let token = match key.as_str() {
+            "fills" => paint_token(fills),
+            "strokes" => paint_token(strokes),
+            "effect" => shadow_token(effects),
+            "text" => node.is_text().map(|(_, style, _)| typography_token(style)),
+            "grid" => node
+                .is_frame()
+                .and_then(|frame| frame.layout_grids.as_ref())
+                .and_then(|grids| grid_token(grids)),
+            _ => None,
+        };
+
+        if let Some(token) = token {
+            insert(tokens, &style.name, token);
+        }
+    }
+
+    for child in node.common().children.iter() {
+        collect(child, styles, tokens, seen);
+    }
+}
+
+/// Adds the token in the groups of the style name, ex: `Primary/Background` is `{ "primary": { "background": {...} } }`.
+/// A token with the name of a group is its `$root` token, ex: `Primary` with `Primary/Hover` is
+/// `{ "primary": { "$root": {...}, "hover": {...} } }`
+fn insert(tokens: &mut Map<String, Value>, name: &str, token: Value) {
+    let path: Vec<String> = name
+        .split('/')
+        .map(|segment| parse_name(&segment.to_string()))
+        .filter(|segment| !segment.is_empty())
+        .collect();
+    let Some((last, groups)) = path.split_last() else {
+        return;
+    };
+
+    let mut group = tokens;
+    for name in groups {
+        let entry = group
+            .entry(name.clone())
+            .or_insert_with(|| Value::Object(Map::new()));
+        if entry.get("$value").is_some() {
+            *entry = json!({ "$root": entry.take() });
+        }
+        match entry.as_object_mut() {
+            Some(g) => group = g,
+            None => return,
+        }
+    }
+
+    match group.get_mut(last) {
+        Some(Value::Object(existing)) if existing.get("$value").is_none() => {
+            existing.entry("$root").or_insert(token);
+        }
+        Some(_) => {}
+        None => {
+            group.insert(last.clone(), token);
+        }
+    }
+}
+
+/// Colour or gradient of the top visible paint
+fn paint_token(paints: &[Paint]) -> Option<Value> {
+    let paint = paints.iter().rev().find(|p| p.visible)?;
+
+    if let Some(colour) = paint.data.get_solid() {
+        return Some(json!({
+            "$type": "color",
+            "$value": colour.with_opacity(paint.opacity).hex(),
+        }));
+    }
+
+    let gradient = paint.data.get_gradient()?;
+    let stops: Vec<Value> = gradient
+        .gradient_stops
+        .iter()
+        .map(|stop| {
+            json!({
+                "color": stop.color.with_opacity(paint.opacity).hex(),
+                "position": round(stop.position),
+            })
+        })
+        .collect();
+
+    Some(json!({ "$type": "gradient", "$value": stops }))
+}
+
+fn shadow_token(effects: &[Effect]) -> Option<Value> {
+    let shadows: Vec<Value> = effects
+        .iter()
+        .filter(|e| e.visible)
+        .filter_map(|effect| {
+            let inset = match effect.effect_type {
+                EffectType::DropShadow => false,
+                EffectType::InnerShadow => true,
+                _ => return None,
+            };
+
+            let mut shadow = json!({
+                "color": effect.color.hex(),
+                "offsetX": px(effect.offset.x()),
+                "offsetY": px(effect.offset.y()),
+                "blur": px(effect.radius),
+                "spread": px(effect.spread),
+            });
+            if inset {
+                shadow["inset"] = Value::Bool(true);
+            }
+
+            Some(shadow)
+        })
+        .collect();
+
+    match shadows.len() {
+        0 => None,
+        1 => Some(json!({ "$type": "shadow", "$value": shadows[0] })),
+        _ => Some(json!({ "$type": "shadow", "$value": shadows })),
+    }
+}
+
+/// The same font properties as the text style tokens of `css/theme.css`
+fn typography_token(style: &TypeStyle) -> Value {
+    let mut token = json!({
+        "$type": "typography",
+        "$value": {
+            "fontFamily": style.font_family,
+            "fontSize": px(style.font_size),
+            "fontWeight": style.font_weight.round() as u32,
+            "lineHeight": round(style.line_height()),
+            "letterSpacing": px(style.letter_spacing),
+        },
+    });
+    let text_transform = style.text_transform();
+    if !text_transform.is_empty() {
+        token["$value"]["textTransform"] = Value::String(text_transform);
+    }
+
+    token
+}
+
+/// Gutter and offset of the first columns or rows grid, and its count when it's fixed
+fn grid_token(grids: &[LayoutGrid]) -> Option<Value> {
+    let grid = grids.iter().find(|g| g.is_layout())?;
+
+    let mut token = json!({
+        "gutter": { "$type": "dimension", "$value": px(grid.gutter_size) },
+        "margin": { "$type": "dimension", "$value": px(grid.offset) },
+    });
+    if grid.count > 0 {
+        token["count"] = json!({ "$type": "number", "$value": grid.count });
+    }
+
+    Some(token)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::FILE_FIXTURE;
+
+    #[test]
+    fn dtcg_tokens() {
+        let file: FigmaData = serde_json::from_str(FILE_FIXTURE).unwrap();
+        let tokens = design_tokens(&file);
+
+        assert_eq!(
+            tokens["primary"]["background"],
+            json!({ "$type": "color", "$value": "#3366ff" })
+        );
+        assert_eq!(tokens["label"]["medium"]["$type"], "typography");
+        assert_eq!(
+            tokens["label"]["medium"]["$value"],
+            json!({
+                "fontFamily": "Inter",
+                "fontSize": "14px",
+                "fontWeight": 600,
+                "lineHeight": 1.2,
+                "letterSpacing": "0px",
+            })
+        );
+    }
+
+    #[test]
+    fn typography_text_transform() {
+        let style: TypeStyle = serde_json::from_value(json!({
+            "fontFamily": "Inter",
+            "fontWeight": 700,
+            "fontSize": 12,
+            "letterSpacing": 0,
+            "textCase": "UPPER",
+        }))
+        .unwrap();
+
+        assert_eq!(
+            typography_token(&style)["$value"]["textTransform"],
+            "uppercase"
+        );
+    }
+
+    #[test]
+    fn groups() {
+        let mut tokens = Map::new();
+        insert(
+            &mut tokens,
+            "Colour/Brand/Primary",
+            json!({ "$value": "#000000" }),
+        );
+        insert(
+            &mut tokens,
+            "Colour/Brand/Primary/Hover",
+            json!({ "$value": "#111111" }),
+        );
+        insert(
+            &mut tokens,
+            "Colour/Brand/Secondary",
+            json!({ "$value": "#222222" }),
+        );
+        // The group is created before its token
+        insert(
+            &mut tokens,
+            "Colour/Accent/Hover",
+            json!({ "$value": "#333333" }),
+        );
+        insert(&mut tokens, "Colour/Accent", json!({ "$value": "#444444" }));
+
+        assert_eq!(
+            Value::Object(tokens),
+            json!({
+                "colour": { "brand": {
+                    "primary": {
+                        "$root": { "$value": "#000000" },
+                        "hover": { "$value": "#111111" },
+                    },
+                    "secondary": { "$value": "#222222" },
+                },
+                "accent": {
+                    "$root": { "$value": "#444444" },
+                    "hover": { "$value": "#333333" },
+                } }
+            })
+        );
+    }
+}
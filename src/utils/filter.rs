@@ -0,0 +1,111 @@
+use crate::types::node::Node;
+
+/// Limits the generation to the matching pages and components, an empty filter matches everything.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Filter {
+    /// Component names, `*` and `?` wildcards are supported
+    pub components: Vec<String>,
+    /// Node ids, ex: `123:45`
+    pub nodes: Vec<String>,
+    /// Page names, `*` and `?` wildcards are supported
+    pub pages: Vec<String>,
+}
+
+impl Filter {
+    pub fn matches_page(&self, page: &Node) -> bool {
+        self.pages.is_empty()
+            || self
+                .pages
+                .iter()
+                .any(|p| glob_match(p, &page.common().name))
+    }
+
+    pub fn matches(&self, node: &Node) -> bool {
+        if self.components.is_empty() && self.nodes.is_empty() {
+            return true;
+        }
+
+        let common = node.common();
+
+        self.nodes.contains(&common.id)
+            || self
+                .components
+                .iter()
+                .any(|c| glob_match(c, &common.name) || glob_match(c, &common.get_name()))
+    }
+}
+
+/// Matches `text` against a pattern where `*` matches any sequence and `?` a single character.
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` in the pattern and the text position it matched up to
+    let mut star: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = star {
+            p = star_p + 1;
+            t = star_t + 1;
+            star = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glob() {
+        assert!(glob_match("Button", "Button"));
+        assert!(!glob_match("Button", "Buttons"));
+        assert!(glob_match("Button*", "Button"));
+        assert!(glob_match("Button*", "Button / Primary"));
+        assert!(glob_match("*/Primary", "Button/Primary"));
+        assert!(glob_match("B?tton", "Button"));
+        assert!(glob_match("*a*b*", "xxaxxbxx"));
+        assert!(!glob_match("*a*b", "xxaxxbxx"));
+        assert!(!glob_match("Input*", "Button"));
+    }
+
+    #[test]
+    fn matches() {
+        let node: Node =
+            serde_json::from_str(r#"{ "type": "CANVAS", "id": "123:45", "name": "Icon Button" }"#)
+                .unwrap();
+
+        assert!(Filter::default().matches(&node));
+        assert!(Filter {
+            nodes: vec!["123:45".to_string()],
+            ..Filter::default()
+        }
+        .matches(&node));
+        assert!(Filter {
+            components: vec!["icon-*".to_string()],
+            ..Filter::default()
+        }
+        .matches(&node));
+        assert!(!Filter {
+            components: vec!["Button*".to_string()],
+            nodes: vec!["1:1".to_string()],
+            ..Filter::default()
+        }
+        .matches(&node));
+        assert!(!Filter {
+            pages: vec!["Forms".to_string()],
+            ..Filter::default()
+        }
+        .matches_page(&node));
+    }
+}
@@ -0,0 +1,57 @@
+use crate::types::node::Node;
+
+/// Prints the node tree, one node per line, indented by depth.
+pub fn inspect(node: &Node, max_depth: Option<usize>) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    inspect_node(node, 0, max_depth, &mut lines);
+    lines.join("\n")
+}
+
+fn inspect_node(node: &Node, depth: usize, max_depth: Option<usize>, lines: &mut Vec<String>) {
+    let common = node.common();
+    let hidden = if common.visible { "" } else { " [hidden]" };
+
+    lines.push(format!(
+        "{}{} \"{}\" ({}){hidden}",
+        "  ".repeat(depth),
+        node.node_type(),
+        common.name,
+        common.id
+    ));
+
+    if max_depth.is_some_and(|max| depth >= max) {
+        return;
+    }
+
+    for child in common.children.iter() {
+        inspect_node(child, depth + 1, max_depth, lines);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inspect_tree() {
+        let node: Node = serde_json::from_str(
+            r#"{
+                "type": "DOCUMENT", "id": "0:0", "name": "Document",
+                "children": [{
+                    "type": "CANVAS", "id": "0:1", "name": "Page 1",
+                    "children": [{ "type": "CANVAS", "id": "1:1", "name": "Nested", "visible": false }]
+                }]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            inspect(&node, None),
+            "DOCUMENT \"Document\" (0:0)\n  CANVAS \"Page 1\" (0:1)\n    CANVAS \"Nested\" (1:1) [hidden]"
+        );
+        assert_eq!(
+            inspect(&node, Some(1)),
+            "DOCUMENT \"Document\" (0:0)\n  CANVAS \"Page 1\" (0:1)"
+        );
+    }
+}
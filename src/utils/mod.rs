@@ -1,7 +1,14 @@
 use convert_case::{Case, Casing};
 
-use crate::types::effect::Effect;
+use crate::{
+    cli::{LengthUnit, NameCase, Naming, Units},
+    types::effect::Effect,
+};
 
+pub mod assets;
+pub mod design_tokens;
+pub mod filter;
+pub mod inspect;
 pub mod parse_components;
 
 pub fn default_visible() -> bool {
@@ -42,6 +49,114 @@ pub fn parse_name(name: &String) -> String {
         .to_case(Case::Kebab)
 }
 
+/// CSS `opacity` of a layer, empty when it's opaque
+pub fn css_opacity(opacity: f32) -> String {
+    let opacity = round(opacity.clamp(0.0, 1.0));
+
+    if opacity < 1.0 {
+        format!("{opacity}")
+    } else {
+        String::new()
+    }
+}
+
+/// Value rounded to 2 decimals, in `f64` so the numbers don't show the `f32` precision, and without "-0"
+pub fn round(value: f32) -> f64 {
+    let rounded = (f64::from(value) * 100.0).round() / 100.0;
+
+    if rounded == 0.0 {
+        0.0
+    } else {
+        rounded
+    }
+}
+
+/// Length in pixels, rounded to 2 decimals
+pub fn px(value: f32) -> String {
+    format!("{}px", round(value))
+}
+
+/// Custom property of a token or variable, ex: `Primary/Background` is `--ds-primary-background` with the `ds` prefix
+pub fn custom_property(name: &str, naming: &Naming) -> String {
+    let name = parse_name(&name.to_string());
+    let name = match &naming.prefix {
+        Some(prefix) => format!("{prefix}-{name}"),
+        None => name,
+    };
+    let case = match naming.case {
+        NameCase::Kebab => Case::Kebab,
+        NameCase::Snake => Case::Snake,
+        NameCase::Camel => Case::Camel,
+    };
+
+    format!("--{}", name.to_case(case))
+}
+
+/// Lengths of the css in the unit of `units`, ex: `24px` is `1.5rem`.
+/// The urls, strings and names are kept as they are.
+pub fn css_units(css: &str, units: &Units) -> String {
+    if units.length == LengthUnit::Px {
+        return css.to_string();
+    }
+
+    let is_name = |c: char| c.is_ascii_alphanumeric() || c == '-' || c == '_';
+    let chars: Vec<char> = css.chars().collect();
+    let mut result = String::with_capacity(css.len());
+    let mut index = 0;
+
+    while index < chars.len() {
+        let rest = &chars[index..];
+        let previous = index.checked_sub(1).map(|i| chars[i]);
+
+        // Copied up to the end of the url or string
+        let end = match rest[0] {
+            'u' if rest.starts_with(&['u', 'r', 'l', '(']) && !previous.is_some_and(is_name) => {
+                rest.iter().position(|c| *c == ')')
+            }
+            quote @ ('"' | '\'') => rest
+                .iter()
+                .enumerate()
+                .skip(1)
+                .find(|(i, c)| **c == quote && rest[i - 1] != '\\')
+                .map(|(i, _)| i),
+            _ => None,
+        };
+        if let Some(end) = end {
+            result.extend(&rest[..=end]);
+            index += end + 1;
+            continue;
+        }
+
+        // A name goes on up to its end, ex: `--space-8px`
+        let in_name = match previous {
+            Some('-') => index >= 2 && is_name(chars[index - 2]),
+            Some(c) => is_name(c),
+            None => false,
+        };
+        let length = rest
+            .iter()
+            .position(|c| !(c.is_ascii_digit() || *c == '.'))
+            .unwrap_or(rest.len());
+
+        if length > 0 && !in_name && rest[length..].starts_with(&['p', 'x']) {
+            let number: String = rest[..length].iter().collect();
+            let after = rest.get(length + 2).copied();
+
+            if let (Ok(number), false) = (number.parse::<f32>(), after.is_some_and(is_name)) {
+                let rem = (number / units.root_font_size * 10000.0).round() / 10000.0;
+                result.push_str(&format!("{rem}rem"));
+                index += length + 2;
+                continue;
+            }
+        }
+
+        result.push(rest[0]);
+        index += 1;
+    }
+
+    result
+}
+
 pub fn token_values(name: String) -> (String, String) {
     let mut variable = format!("--{}", parse_name(&name));
     let mut theme = String::from(":root");
@@ -58,3 +173,48 @@ pub fn token_values(name: String) -> (String, String) {
 
     (variable, theme)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rounding() {
+        assert_eq!(px(10.126), "10.13px");
+        assert_eq!(px(-0.001), "0px");
+        assert_eq!(round(0.1).to_string(), "0.1");
+    }
+
+    #[test]
+    fn custom_properties() {
+        let naming = Naming {
+            prefix: Some("ds".to_string()),
+            case: NameCase::Snake,
+        };
+        assert_eq!(
+            custom_property("Primary/Background", &naming),
+            "--ds_primary_background"
+        );
+        assert_eq!(
+            custom_property("Primary/Background", &Naming::default()),
+            "--primary-background"
+        );
+    }
+
+    #[test]
+    fn rem_units() {
+        let units = Units {
+            length: LengthUnit::Rem,
+            root_font_size: 16.0,
+        };
+
+        assert_eq!(
+            css_units(
+                ".card{gap:12px;margin:-8px 0;--space-8px:4px;background:url(\"img/16px.png\")}",
+                &units
+            ),
+            ".card{gap:0.75rem;margin:-0.5rem 0;--space-8px:0.25rem;background:url(\"img/16px.png\")}"
+        );
+        assert_eq!(css_units("gap: 12px;", &Units::default()), "gap: 12px;");
+    }
+}
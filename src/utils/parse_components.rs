@@ -1,15 +1,27 @@
-use std::collections::{BTreeMap, HashMap, HashSet};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    path::Path,
+};
 
 use crate::{
+    cli::{Naming, OutputConfig, Units},
+    generator::{self, Element, Generator, Library},
     types::{
         component::{Component, ComponentSet},
         file::FigmaData,
         frame::Frame,
         node::Node,
-        styles::Style,
-        token::Token,
+        styles::{Style, TypeStyle},
+        token::{token_id, Token},
+        variables::Variables,
+    },
+    utils::{
+        assets::{asset_paths, collect_assets, image_paths, ImageFile, ASSETS_DIR},
+        css_units, custom_property,
+        design_tokens::write_design_tokens,
+        filter::Filter,
+        token_values,
     },
-    utils::token_values,
 };
 
 use askama::Template;
@@ -25,130 +37,185 @@ struct CssTemplate<'a> {
     rules: &'a Vec<(&'a String, &'a String)>,
 }
 
-#[derive(Template, Debug, PartialEq, Eq, Hash)]
-#[template(path = "markup.html", escape = "none")]
-struct MarkupTemplate {
-    tag: String,
-    classes: String,
-    characters: String,
-    children: Vec<MarkupTemplate>,
-}
-
 #[derive(Template, Debug)]
 #[template(path = "theme.html", escape = "none")]
 struct ThemeTemplate {
     rules: BTreeMap<String, Vec<String>>,
+    /// Utility classes of the text styles, ex: `text-heading-h1`
+    utilities: Vec<(String, Vec<String>)>,
 }
 
-#[derive(Template, Debug)]
-#[template(path = "component.html", escape = "none")]
-struct ComponentTemplate {
-    name: String,
-    styles: String,
-    markup: String,
-    tokens: String,
-}
-
-#[derive(Clone)]
-struct ComponentData {
-    name: String,
-    css: String,
-    html: String,
-    includes: Vec<String>,
+/// Data shared by the generation of every node
+struct Context<'a> {
+    components: &'a HashMap<String, Component>,
+    component_sets: &'a HashMap<String, ComponentSet>,
+    tokens: &'a HashMap<String, Token>,
+    /// Image of the exported nodes, relative to the `assets/` folder
+    assets: &'a HashMap<String, String>,
+    /// Downloaded image fills by image ref, relative to the `assets/` folder
+    images: &'a HashMap<String, ImageFile>,
+    variables: &'a Variables,
 }
 
-pub fn parse(file: FigmaData) {
-    let components = file.components;
-    let component_sets = file.component_sets;
+/// Generates the selected components with the `generator`, after the tokens and `css/theme.css`
+pub fn parse(
+    file: FigmaData,
+    output: &OutputConfig,
+    filter: &Filter,
+    variables: &Variables,
+    generator: &dyn Generator,
+) {
+    let out_dir = output.out_dir.as_path();
+    let variables = &Variables {
+        naming: output.naming.clone(),
+        ..variables.clone()
+    };
+    let tokens = collect_tokens(&file, &output.naming);
+    write_tokens(out_dir, &tokens, variables, &output.units);
+    write_design_tokens(&file, out_dir);
+    let assets = asset_paths(&collect_assets(&file.document));
+    let images = image_paths(&out_dir.join(ASSETS_DIR));
+    let context = Context {
+        components: &file.components,
+        component_sets: &file.component_sets,
+        tokens: &tokens,
+        assets: &assets,
+        images: &images,
+        variables,
+    };
     let pages = file.document.common().children.iter();
-    let styles = file.styles;
     let parent_frame = Frame {
         ..Default::default()
     };
-    let mut tokens: HashMap<String, Token> = HashMap::new();
-    let mut data: BTreeMap<String, ComponentData> = BTreeMap::new();
+    let mut data: BTreeMap<String, generator::Component> = BTreeMap::new();
+
+    // Every component is a candidate, because the selected ones can include instances of components from any page
+    let mut candidates: HashMap<&String, &Node> = HashMap::new();
+    let mut selected: Vec<String> = Vec::new();
 
     for page in pages {
-        let nodes = page.common().children.iter();
+        for node in page.common().children.iter() {
+            if node.is_component_or_set().is_some() {
+                candidates.insert(&node.common().id, node);
 
-        // Get tokens before generating components
-        for node in nodes.clone() {
-            if let Some(_) = node.is_frame() {
-                generate_tokens(node, &styles, &mut tokens);
+                if filter.matches_page(page) && filter.matches(node) {
+                    selected.push(node.common().id.clone());
+                }
             }
         }
+    }
 
-        for node in nodes {
-            if let Some(_) = node.is_component_or_set() {
-                let mut element: Vec<MarkupTemplate> = Vec::new();
-                let mut css: Vec<String> = Vec::new();
-                // Grabs the instances id's to get the extra css need for a component
-                let mut includes: Vec<String> = Vec::new();
+    let mut queue: Vec<String> = selected.clone();
 
-                generate(
-                    node,
-                    &parent_frame,
-                    &String::new(),
-                    &mut element,
-                    &mut css,
-                    &mut includes,
-                    false,
-                    &components,
-                    &component_sets,
-                    &tokens,
-                );
-
-                includes.dedup(); // remove duplicates
+    while let Some(id) = queue.pop() {
+        if data.contains_key(&id) {
+            continue;
+        }
 
-                data.insert(
-                    node.common().id.clone(),
-                    ComponentData {
-                        name: node.common().get_name(),
-                        css: create_css(css.join("\n")),
-                        html: create_markup(element, node.is_component_set().is_some()),
-                        includes,
-                    },
-                );
-            }
+        if let Some(node) = candidates.get(&id) {
+            let mut element: Vec<Element> = Vec::new();
+            // Grabs the instances id's to get the extra css need for a component
+            let mut includes: Vec<String> = Vec::new();
+
+            generate(
+                node,
+                &parent_frame,
+                &String::new(),
+                &mut element,
+                &mut includes,
+                false,
+                &context,
+            );
+
+            // Remove duplicates, keeping the order of the first use
+            let mut seen = HashSet::new();
+            includes.retain(|id| seen.insert(id.clone()));
+
+            // The included components need to be generated for their css, even when not selected
+            queue.extend(includes.iter().cloned());
+
+            data.insert(
+                id.clone(),
+                generator::Component {
+                    id,
+                    name: node.common().get_name(),
+                    css: css_units(
+                        &create_css(element.iter().map(element_css).collect()),
+                        &output.units,
+                    ),
+                    markup: element,
+                    is_set: node.is_component_set().is_some(),
+                    includes,
+                },
+            );
         }
     }
 
-    // TODO: to many clones :/
-    for (_, dt) in &data {
-        let mut css: Vec<String> = Vec::new();
+    let library = Library {
+        components: &data,
+        tokens: &tokens,
+        variables,
+        units: &output.units,
+    };
 
-        // This means that the component has instances of other components so we need to add the css of those
-        for id in &dt.includes {
-            if let Some(values) = data.get(id) {
-                css.push(values.css.clone());
-            }
+    for (id, component) in data.iter() {
+        if !generator.generates_dependencies() && !selected.contains(id) {
+            continue;
         }
 
-        css.push(dt.css.clone());
+        for (file_type, content) in generator.generate(component, &library) {
+            write_files(out_dir, component.name.clone(), content, &file_type);
+        }
+    }
+}
 
-        let cmp = ComponentTemplate {
-            name: dt.name.clone(),
-            styles: css.join(""),
-            markup: dt.html.clone(),
-            tokens: write_tokens(&tokens),
-        };
+/// Only generates the tokens, `css/theme.css` and `tokens.json`, without the components.
+pub fn parse_tokens(file: &FigmaData, output: &OutputConfig, variables: &Variables) {
+    let variables = &Variables {
+        naming: output.naming.clone(),
+        ..variables.clone()
+    };
+    let tokens = collect_tokens(file, &output.naming);
+    write_tokens(&output.out_dir, &tokens, variables, &output.units);
+    write_design_tokens(file, &output.out_dir);
+}
+
+fn collect_tokens(file: &FigmaData, naming: &Naming) -> HashMap<String, Token> {
+    let mut tokens: HashMap<String, Token> = HashMap::new();
+
+    for page in file.document.common().children.iter() {
+        for node in page.common().children.iter() {
+            if node.is_frame().is_some() {
+                generate_tokens(node, &file.styles, &mut tokens);
+            }
+        }
+    }
 
-        write_files(dt.name.clone(), cmp.render().unwrap(), "html");
+    // The css of the nodes references the tokens by their custom property
+    for token in tokens.values_mut() {
+        token.variable = custom_property(&token.variable, naming);
     }
+
+    tokens
 }
 
 fn generate(
     node: &Node,
     parent_frame: &Frame,
     parent_classes: &String,
-    element: &mut Vec<MarkupTemplate>,
-    css: &mut Vec<String>,
+    element: &mut Vec<Element>,
     includes: &mut Vec<String>,
     is_instance: bool,
-    components: &HashMap<String, Component>,
-    component_sets: &HashMap<String, ComponentSet>,
-    tokens: &HashMap<String, Token>,
+    context: &Context,
 ) {
+    // Exported shapes and icons are delivered as images, the other exported frames keep their markup
+    if let Some(asset) = context.assets.get(&node.common().id) {
+        if node.is_graphic() {
+            element.push(image_markup(node, asset));
+            return;
+        }
+    }
+
     if let Some(frame) = node.is_frame() {
         let classes = format!(
             "{parent_classes}{current_classes}",
@@ -157,6 +224,7 @@ fn generate(
 
         let mut variant_classes = String::new();
         let mut variant_name = frame.node.name.clone();
+        let mut component = None;
 
         if frame.is_variant() {
             variant_classes = parent_frame.get_name();
@@ -165,11 +233,11 @@ fn generate(
         // Get correct name and classes for instance, because instance name does not contain variants, so we need to get the info from components
         // and component_sets
         if let Some((_, component_id)) = node.is_instance() {
-            if let Some(cmp) = components.get(component_id) {
+            if let Some(cmp) = context.components.get(component_id) {
                 variant_name = cmp.get_name();
                 let mut id = component_id.to_string();
 
-                if let Some(set) = component_sets.get(&cmp.component_set_id) {
+                if let Some(set) = context.component_sets.get(&cmp.component_set_id) {
                     id = cmp.component_set_id.to_string();
                     variant_classes = if set.name.eq(&frame.node.name) {
                         frame.node.get_name()
@@ -178,44 +246,48 @@ fn generate(
                     }
                 }
 
-                includes.push(id);
+                includes.push(id.clone());
+                component = Some(id);
             }
         }
 
         // TODO: select the element tag ex: when to use <button></button> instead of <div></div>
-        let mut element_markup = MarkupTemplate {
+        let mut element_markup = Element {
             tag: "div".to_string(),
-            classes: frame.get_markup_attributes(variant_classes, variant_name),
-            characters: String::new(),
-            children: Vec::new(),
+            variant: frame.variant_attributes(&variant_name),
+            attributes: frame.get_markup_attributes(variant_classes, variant_name),
+            selector: classes.trim().to_string(),
+            component,
+            ..Default::default()
         };
 
         if let None = node.is_component_set() {
             if !is_instance {
-                let element_css = frame.css(parent_frame.clone(), tokens);
-                css.push(get_styles(
-                    &classes,
-                    &element_css.iter().collect::<Vec<(&String, &String)>>(),
-                ));
+                element_markup.rules =
+                    frame.css(parent_frame.clone(), context.tokens, context.images);
+                frame.bind_variables(&mut element_markup.rules, context.variables, context.images);
             }
         }
 
         for child in frame.node.children.iter() {
             if let Some((vector, style, characters)) = child.is_text() {
-                let text_css = vector.css(style);
+                let mut text_css = vector.css(style, context.tokens);
+                text_css.extend(vector.position(frame));
+                vector.bind_variables(&mut text_css, context.variables);
                 let text_classes = format!("{classes} .{}", vector.get_name());
 
-                if !is_instance {
-                    css.push(get_styles(
-                        &text_classes,
-                        &text_css.iter().collect::<Vec<(&String, &String)>>(),
-                    ));
-                }
-                element_markup.children.push(MarkupTemplate {
+                element_markup.children.push(Element {
                     tag: "span".to_string(),
-                    classes: format!(" class=\"{}\"", vector.get_name()),
+                    attributes: vec![("class".to_string(), vector.get_name())],
+                    selector: text_classes.trim().to_string(),
+                    rules: if is_instance {
+                        BTreeMap::new()
+                    } else {
+                        text_css
+                    },
                     characters: characters.into(),
-                    children: Vec::new(),
+                    slot: Some(vector.get_name()),
+                    ..Default::default()
                 });
             } else {
                 let condition = match child.is_instance() {
@@ -227,12 +299,9 @@ fn generate(
                     frame,
                     &classes,
                     &mut element_markup.children,
-                    css,
                     includes,
                     condition,
-                    components,
-                    component_sets,
-                    tokens,
+                    context,
                 );
             }
         }
@@ -240,6 +309,29 @@ fn generate(
     }
 }
 
+fn image_markup(node: &Node, asset: &String) -> Element {
+    let common = node.common();
+    // The files of the components are in `components/<name>/`
+    let mut attributes = vec![
+        ("class".to_string(), common.get_name()),
+        ("src".to_string(), format!("../../{ASSETS_DIR}/{asset}")),
+        ("alt".to_string(), common.name.clone()),
+    ];
+
+    if let Some(bounding_box) = node.bounding_box() {
+        if let (Some(width), Some(height)) = (bounding_box.width, bounding_box.height) {
+            attributes.push(("width".to_string(), width.to_string()));
+            attributes.push(("height".to_string(), height.to_string()));
+        }
+    }
+
+    Element {
+        tag: "img".to_string(),
+        attributes,
+        ..Default::default()
+    }
+}
+
 fn generate_tokens(
     node: &Node,
     styles: &HashMap<String, Style>,
@@ -256,13 +348,19 @@ fn generate_tokens(
         // }
         if let Some(style) = &frame.styles {
             for (key, id) in style.iter() {
+                if key == "grid" {
+                    if let Some(s) = styles.get(id) {
+                        generate_grid_tokens(frame, id, &s.name, tokens);
+                    }
+                    continue;
+                }
+
                 if tokens.get(id).is_none() {
                     if let Some(s) = styles.get(id) {
                         let value = match key.as_str() {
                             "fills" => frame.background(),
                             "strokes" => frame.border_colour(),
                             "effect" => frame.box_shadow(None),
-                            "grid" => String::new(), // TODO
                             _ => String::new(),
                         };
 
@@ -273,6 +371,7 @@ fn generate_tokens(
                                 variable,
                                 value,
                                 theme,
+                                property: None,
                             };
                             tokens.insert(id.clone(), token);
                         }
@@ -283,13 +382,19 @@ fn generate_tokens(
 
         // TODO: improve this multiple condition chaining
         for child in frame.node.children.iter() {
-            if let Some((vector, _, _)) = child.is_text() {
+            if let Some((vector, type_style, _)) = child.is_text() {
                 if let Some(style) = &vector.styles {
                     for (key, id) in style.iter() {
+                        if key == "text" {
+                            if let Some(s) = styles.get(id) {
+                                generate_text_tokens(type_style, id, &s.name, tokens);
+                            }
+                            continue;
+                        }
+
                         if tokens.get(id).is_none() {
                             if let Some(s) = styles.get(id) {
                                 let value = match key.as_str() {
-                                    "text" => String::new(), // TODO: this one needs to generate multiple css variables and maybe a class to add to component css
                                     "fills" => vector.text_colour(),
                                     "strokes" => vector.border_colour(),
                                     "effect" => vector.box_shadow(),
@@ -303,6 +408,7 @@ fn generate_tokens(
                                         variable,
                                         value,
                                         theme,
+                                        property: None,
                                     };
                                     tokens.insert(id.clone(), token);
                                 }
@@ -316,30 +422,67 @@ fn generate_tokens(
     }
 }
 
+/// A grid style gives a token for each value of its grids, ex: `--grid-columns` and `--grid-gutter`
+fn generate_grid_tokens(frame: &Frame, id: &str, name: &str, tokens: &mut HashMap<String, Token>) {
+    let (variable, theme) = token_values(name.to_string());
+
+    for (suffix, value) in frame.grid_tokens() {
+        tokens.entry(token_id(id, suffix)).or_insert_with(|| Token {
+            name: name.to_string(),
+            variable: format!("{variable}-{suffix}"),
+            value,
+            theme: theme.clone(),
+            property: None,
+        });
+    }
+}
+
+/// A text style gives a token for each font property, ex: `--heading-h1-font-size`,
+/// and a `text-heading-h1` utility class using them
+fn generate_text_tokens(
+    style: &TypeStyle,
+    id: &str,
+    name: &str,
+    tokens: &mut HashMap<String, Token>,
+) {
+    let (variable, theme) = token_values(name.to_string());
+
+    for (property, value) in style.font_rules() {
+        tokens
+            .entry(token_id(id, property))
+            .or_insert_with(|| Token {
+                name: name.to_string(),
+                variable: format!("{variable}-{property}"),
+                value,
+                theme: theme.clone(),
+                property: Some(property.to_string()),
+            });
+    }
+}
+
 fn get_styles(classes: &String, rules: &Vec<(&String, &String)>) -> String {
     let css_template = CssTemplate { classes, rules };
 
     css_template.render().unwrap()
 }
 
-fn create_markup(values: Vec<MarkupTemplate>, is_set: bool) -> String {
-    let mut content = values[0].render().unwrap();
+/// Rules of the element and its children, in the order of the markup
+fn element_css(element: &Element) -> String {
+    let mut css = String::new();
 
-    if is_set {
-        // content = format!("{}\n", values[0].children[0].render().unwrap());
-        content = String::new();
-        let mut seen = HashSet::new();
+    if !element.rules.is_empty() {
+        css.push_str(&get_styles(
+            &element.selector,
+            &element.rules.iter().collect::<Vec<(&String, &String)>>(),
+        ));
+        css.push('\n');
+    }
 
-        // Remove duplicate markup
-        for child in &values[0].children {
-            if !seen.contains(&child) {
-                seen.insert(child);
-                content.push_str(&format!("{}\n", child.render().unwrap()));
-            }
-        }
+    for child in element.children.iter() {
+        css.push_str(&element_css(child));
     }
 
-    content
+    css
 }
 
 fn create_css(content: String) -> String {
@@ -357,7 +500,24 @@ fn create_css(content: String) -> String {
     res.code
 }
 
-fn write_tokens(tokens: &HashMap<String, Token>) -> String {
+fn write_tokens(
+    out_dir: &Path,
+    tokens: &HashMap<String, Token>,
+    variables: &Variables,
+    units: &Units,
+) {
+    let content = theme_css(tokens, variables, units);
+
+    if !content.is_empty() {
+        let css_dir = out_dir.join("css");
+        let _ = std::fs::create_dir_all(&css_dir);
+
+        let _ = std::fs::write(css_dir.join("theme.css"), &content);
+    }
+}
+
+/// Custom properties of the tokens and variables in each theme, with the utility classes of the text styles
+pub fn theme_css(tokens: &HashMap<String, Token>, variables: &Variables, units: &Units) -> String {
     let mut tk: BTreeMap<String, Vec<String>> = BTreeMap::new();
 
     for (_, token) in tokens.iter() {
@@ -372,54 +532,200 @@ fn write_tokens(tokens: &HashMap<String, Token>) -> String {
         }
     }
 
-    let tmp = ThemeTemplate { rules: tk.clone() };
-
-    let content = tmp.render().unwrap();
+    // Variables modes use the same themes as the styles
+    for (theme, rules) in variables.css() {
+        let theme_rules = tk.entry(theme).or_default();
+        theme_rules.extend(rules);
+        theme_rules.sort_by_key(|a| a.to_lowercase());
+    }
 
-    if !content.is_empty() {
-        let _ = std::fs::create_dir_all(format!("figma_output/css"));
+    // Utility classes of the text styles, the same in every theme
+    let mut utilities: Vec<(String, Vec<String>)> = Vec::new();
+    for token in tokens.values() {
+        if let Some(property) = &token.property {
+            let (variable, _) = token_values(token.name.clone());
+            let mut class = format!("text-{}", variable.trim_start_matches("--"));
+            // The class of a theme with the same name would be overridden by the utility class
+            while tk.contains_key(&class) {
+                class = format!("{class}-style");
+            }
+            let rule = format!("{property}: var({});", token.variable);
 
-        let _ = std::fs::write(format!("figma_output/css/theme.css"), format!("{content}"));
+            match utilities.iter_mut().find(|(c, _)| c == &class) {
+                Some((_, rules)) if !rules.contains(&rule) => rules.push(rule),
+                Some(_) => {}
+                None => utilities.push((class, vec![rule])),
+            }
+        }
+    }
+    utilities.sort_by_key(|(class, _)| class.to_lowercase());
+    for (_, rules) in utilities.iter_mut() {
+        rules.sort_by_key(|rule| rule.to_lowercase());
     }
 
-    content
+    let tmp = ThemeTemplate {
+        rules: tk,
+        utilities,
+    };
+
+    css_units(&tmp.render().unwrap(), units)
 }
 
-fn write_files(name: String, content: String, file_type: &str) {
+fn write_files(out_dir: &Path, name: String, content: String, file_type: &str) {
     if !content.is_empty() {
-        let _ = std::fs::create_dir_all(format!("figma_output/components/{name}"));
+        let component_dir = out_dir.join("components").join(&name);
+        let _ = std::fs::create_dir_all(&component_dir);
 
-        let _ = std::fs::write(
-            format!("figma_output/components/{name}/{name}.{file_type}"),
-            format!("{content}"),
-        );
+        let _ = std::fs::write(component_dir.join(format!("{name}.{file_type}")), content);
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::cli::{LengthUnit, NameCase};
+    use crate::test::FILE_FIXTURE;
     use std::fs;
     use std::io::Read;
     use tempfile;
 
+    /// In-house target listing the variant attributes of each component
+    struct VariantList;
+
+    impl Generator for VariantList {
+        fn generate(&self, component: &generator::Component, _: &Library) -> Vec<(String, String)> {
+            let variants: Vec<String> = component
+                .variants()
+                .iter()
+                .flat_map(|element| element.variant.iter())
+                .map(|(attribute, value)| format!("{attribute}={value}"))
+                .collect();
+
+            vec![("txt".to_string(), variants.join("\n"))]
+        }
+    }
+
+    fn output(dir: &Path) -> OutputConfig {
+        OutputConfig {
+            out_dir: dir.to_path_buf(),
+            cache_dir: None,
+            target: Default::default(),
+            tag_prefix: "fg".to_string(),
+            naming: Naming::default(),
+            units: Units::default(),
+        }
+    }
+
     #[test]
-    fn test_write_files() {
-        // Create a temporary directory
+    fn custom_generator() {
+        let file: FigmaData = serde_json::from_str(FILE_FIXTURE).unwrap();
+        let dir = tempfile::tempdir().unwrap();
+
+        parse(
+            file,
+            &output(dir.path()),
+            &Filter::default(),
+            &Variables::default(),
+            &VariantList,
+        );
+
+        let components = dir.path().join("components");
+        assert_eq!(
+            fs::read_to_string(components.join("button/button.txt")).unwrap(),
+            "size=small\nsize=large"
+        );
+        // Nothing to write for the components without variants
+        assert!(!components.join("card/card.txt").exists());
+    }
+
+    #[test]
+    fn naming_and_units() {
+        let file: FigmaData = serde_json::from_str(FILE_FIXTURE).unwrap();
         let dir = tempfile::tempdir().unwrap();
+        let output = OutputConfig {
+            naming: Naming {
+                prefix: Some("ds".to_string()),
+                case: NameCase::Snake,
+            },
+            units: Units {
+                length: LengthUnit::Rem,
+                root_font_size: 16.0,
+            },
+            ..output(dir.path())
+        };
 
-        // Set the current working directory to the temporary directory
-        std::env::set_current_dir(dir.path()).unwrap();
+        parse(
+            file,
+            &output,
+            &Filter::default(),
+            &Variables::default(),
+            &crate::generator::html::Html,
+        );
+
+        let theme = fs::read_to_string(dir.path().join("css/theme.css")).unwrap();
+        assert!(theme.contains("--ds_label_medium_font_size: 0.875rem;"));
+        let button = fs::read_to_string(dir.path().join("components/button/button.html")).unwrap();
+        assert!(button.contains("background: var(--ds_primary_background);"));
+        assert!(button.contains("padding: 0.5rem 0.75rem;"));
+    }
+
+    #[test]
+    fn theme_utility_classes() {
+        let token = |name: &str, value: &str, property: Option<&str>| {
+            let (mut variable, theme) = token_values(name.to_string());
+            if let Some(property) = property {
+                variable = format!("{variable}-{property}");
+            }
+            Token {
+                name: name.to_string(),
+                variable,
+                value: value.to_string(),
+                theme,
+                property: property.map(|p| p.to_string()),
+            }
+        };
+        // The utility class of the text style would have the name of the theme
+        let tokens = HashMap::from([
+            ("1".to_string(), token("Text theme/Primary", "#000", None)),
+            (
+                "2".to_string(),
+                token("Theme", "Inter", Some("font-family")),
+            ),
+            ("3".to_string(), token("Theme", "24px", Some("font-size"))),
+        ]);
+
+        let css = theme_css(&tokens, &Variables::default(), &Units::default());
+        assert_eq!(css.matches(".text-theme {").count(), 1);
+        assert_eq!(
+            css,
+            ":root {
+    --theme-font-family: Inter;
+    --theme-font-size: 24px;
+}
+.text-theme {
+    --primary: #000;
+}
+.text-theme-style {
+    font-family: var(--theme-font-family);
+    font-size: var(--theme-font-size);
+}"
+        );
+    }
+
+    #[test]
+    fn test_write_files() {
+        // Create a temporary directory to use as the output directory
+        let dir = tempfile::tempdir().unwrap();
 
         // Define the name and styles
         let name = "test".to_string();
         let styles = ".test { color: red; }".to_string();
 
         // Call the function
-        write_files(name.clone(), styles.clone(), "css");
+        write_files(dir.path(), name.clone(), styles.clone(), "css");
 
         // Check if the styles were written to the correct file
-        let path = format!("figma_output/components/{name}/{name}.css");
+        let path = dir.path().join(format!("components/{name}/{name}.css"));
         assert!(fs::metadata(&path).is_ok());
 
         // Read the contents of the file
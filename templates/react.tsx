@@ -0,0 +1,29 @@
+{% if has_texts -%}
+import type { ReactNode } from "react";
+{% endif -%}
+{% for (component, file) in imports -%}
+import {{ component }} from "../{{ file }}/{{ file }}";
+{% endfor -%}
+{% for (asset, path) in assets -%}
+import {{ asset }} from "{{ path }}";
+{% endfor -%}
+import styles from "./{{ file }}.module.css";
+
+export interface {{ name }}Props {
+{%- for (prop, prop_type) in props %}
+  {{ prop }}?: {{ prop_type }};
+{%- endfor %}
+}
+
+export default function {{ name }}({{ params }}: {{ name }}Props) {
+{%- for (condition, jsx) in variants %}
+  if ({{ condition }}) {
+    return (
+      {{ jsx|indent(6) }}
+    );
+  }
+{% endfor %}
+  return (
+    {{ fallback|indent(4) }}
+  );
+}
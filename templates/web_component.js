@@ -0,0 +1,67 @@
+{% for import in imports -%}
+import "../{{ import }}/{{ import }}.js";
+{% endfor %}
+// The paths of the assets are relative to the module, not to the page using the element
+const styles = new CSSStyleSheet({ baseURL: import.meta.url });
+styles.replaceSync(`{{ styles }}`);
+
+const variants = [
+{%- for (attributes, markup) in variants %}
+  {
+    attributes: {{ attributes }},
+    markup: `
+    {{ markup|indent(4) }}`,
+  },
+{%- endfor %}
+];
+
+export class {{ class_name }} extends HTMLElement {
+  static get observedAttributes() {
+    return {{ observed }};
+  }
+
+  constructor() {
+    super();
+    this.attachShadow({ mode: "open" });
+    this.shadowRoot.adoptedStyleSheets = [styles];
+  }
+
+  connectedCallback() {
+    this.render();
+  }
+
+  attributeChangedCallback() {
+    this.render();
+  }
+
+  // Renders the variant matching the most attributes, an empty value matches a missing attribute
+  render() {
+    let variant = variants[0];
+    let best = -1;
+
+    for (const candidate of variants) {
+      const matches = Object.entries(candidate.attributes).filter(
+        ([name, value]) => (this.getAttribute(name) ?? "") === value
+      ).length;
+
+      if (matches > best) {
+        variant = candidate;
+        best = matches;
+      }
+    }
+
+    // The content of a template is inert, the images aren't loaded before their src is resolved
+    const template = document.createElement("template");
+    template.innerHTML = variant.markup;
+
+    for (const img of template.content.querySelectorAll("img[src]")) {
+      img.src = new URL(img.getAttribute("src"), import.meta.url).href;
+    }
+
+    this.shadowRoot.replaceChildren(template.content);
+  }
+}
+
+if (!customElements.get("{{ tag }}")) {
+  customElements.define("{{ tag }}", {{ class_name }});
+}